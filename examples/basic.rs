@@ -1,18 +1,24 @@
 use bevy::{
     app::App,
     prelude::Color,
-    prelude::{ClearColor, Commands, Name, Startup, Vec2},
+    prelude::{AssetServer, BuildChildren, ClearColor, Commands, Name, Res, Startup, Vec2},
     DefaultPlugins,
 };
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-use bevy_mod_transform2d::transform2d::Transform2d;
+use bevy_mod_transform2d::{prelude::Spatial2dBundle, transform2d::Transform2d};
 use bevy_rapier2d::{
     plugin::{NoUserData, RapierPhysicsPlugin},
     prelude::{Collider, RigidBody},
 };
 use bevy_twin_stick::{
-    actors::ActorBundle, ai::keyboard::KeyboardAI, bevy_rapier2d::render::RapierDebugRenderPlugin,
-    player::Player, stats::Speed, transform2d_mods::Sprite2dBundle, TwinStickPlugin,
+    actors::ActorBundle,
+    ai::keyboard::KeyboardAI,
+    bevy_rapier2d::render::RapierDebugRenderPlugin,
+    player::Player,
+    stats::Speed,
+    transform2d_mods::Sprite2dBundle,
+    weapons::{definition::WeaponDefinition, muzzle::Muzzles},
+    TwinStickPlugin,
 };
 
 pub fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -34,14 +40,23 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn setup(mut commands: Commands) {
-    commands.spawn((
-        Player,
-        Name::new("Player"),
-        ActorBundle::default(),
-        Speed(4000.),
-        KeyboardAI,
-    ));
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            Player,
+            Name::new("Player"),
+            ActorBundle::default(),
+            Speed(4000.),
+            KeyboardAI,
+        ))
+        .with_children(|player| {
+            player.spawn((
+                Name::new("Shotgun"),
+                Spatial2dBundle::default(),
+                asset_server.load::<WeaponDefinition>("weapons/shotgun.weapon.ron"),
+                Muzzles::forward(20.),
+            ));
+        });
 
     commands.spawn((
         Sprite2dBundle {
@@ -2,8 +2,8 @@ use bevy::{
     math::Vec3Swizzles,
     prelude::{
         in_state, App, Bundle, Changed, Commands, Component, DespawnRecursiveExt, Entity,
-        GlobalTransform, InheritedVisibility, IntoSystemConfigs, Parent, Plugin, Query, Transform,
-        Update, Vec2, Visibility, With, Without,
+        GlobalTransform, InheritedVisibility, IntoSystemConfigs, Or, Parent, Plugin, Query,
+        Transform, Update, Vec2, Visibility, With, Without,
     },
     reflect::Reflect,
 };
@@ -23,6 +23,10 @@ pub struct Actor {
     pub desired_target: Option<Entity>,
 }
 
+/// What projectiles and hitscan rays treat as an actor they hit, rather than scenery they run
+/// into.
+pub type Hittable = Or<(With<Actor>, With<Health>)>;
+
 #[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Component)]
 pub enum Faction {
     FactionID(usize),
@@ -30,6 +34,28 @@ pub enum Faction {
     HostileToAll,
 }
 
+impl Faction {
+    /// Whether this faction goes after actors of `other`, e.g. with aim assist or homing.
+    /// `HostileToAll` goes after everyone and is fair game for everyone, itself included.
+    /// `FriendlyToAll` is left alone, but still goes after any `FactionID`, so a player left at
+    /// the `ActorBundle` default picks out enemies that have a faction of their own.
+    pub fn is_hostile_to(&self, other: &Faction) -> bool {
+        match (self, other) {
+            (Faction::HostileToAll, _) | (_, Faction::HostileToAll) => true,
+            (_, Faction::FriendlyToAll) => false,
+            (Faction::FriendlyToAll, Faction::FactionID(_)) => true,
+            (Faction::FactionID(a), Faction::FactionID(b)) => a != b,
+        }
+    }
+
+    /// Whether the two factions are on the same side, so their attacks shouldn't hurt each other.
+    /// Only matching `FactionID`s are; `FriendlyToAll` actors don't attack anyone, but can still
+    /// be hit.
+    pub fn is_allied_with(&self, other: &Faction) -> bool {
+        matches!((self, other), (Faction::FactionID(a), Faction::FactionID(b)) if a == b)
+    }
+}
+
 impl Default for Actor {
     fn default() -> Self {
         Self {
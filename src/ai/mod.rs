@@ -4,15 +4,18 @@ use bevy::prelude::{App, IntoSystemConfigs, Plugin, Query, Update};
 use crate::{
     actors::{actor_movement, Actor},
     player::player_exists,
+    weapons::WeaponTriggerSet,
 };
 
 use self::{
     keyboard::keyboard_input_handler,
+    shooter::{do_shooter_ai, ShooterAI},
     tracking::do_tracker_ai,
     wander::{ai_wander, PerlinWanderAI},
 };
 
 pub mod keyboard;
+pub mod shooter;
 pub mod tracking;
 pub mod wander;
 
@@ -29,9 +32,11 @@ impl Plugin for AIPlugin {
                 ai_wander,
                 normalize_ai.after(do_tracker_ai).after(ai_wander),
                 actor_movement.after(normalize_ai),
+                do_shooter_ai.after(do_tracker_ai).in_set(WeaponTriggerSet),
             ),
         );
-        app.register_type::<PerlinWanderAI>();
+        app.register_type::<PerlinWanderAI>()
+            .register_type::<ShooterAI>();
     }
 }
 
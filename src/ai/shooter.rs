@@ -0,0 +1,222 @@
+use bevy::{
+    prelude::{Children, Component, Entity, EventWriter, Query, Res, Vec2, Without},
+    reflect::Reflect,
+    time::{Time, Timer, TimerMode},
+};
+use bevy_mod_transform2d::transform2d::Transform2d;
+use bevy_rapier2d::prelude::{QueryFilter, RapierContext};
+
+use crate::{
+    actors::Actor,
+    player::Player,
+    weapons::{
+        charge::{ChargeState, ReleaseChargeEvent, StartChargeEvent},
+        inventory::WeaponInventory,
+        FireWeaponEvent, Weapon, WeaponAction, WeaponFireMode,
+    },
+};
+
+/// Fires the actor's weapons at its `desired_target` once the target has been in range and in
+/// line of sight for the reaction delay. Give the actor an `AimSpread` to make it miss.
+#[derive(Component, Clone, PartialEq, Reflect, Debug)]
+pub struct ShooterAI {
+    pub range: f32,
+    pub reaction_timer: Timer,
+    /// The target currently in sight, if any.
+    pub spotted: Option<Entity>,
+    /// `SemiAuto` and `Burst` weapons whose trigger was pulled last frame. Like a player, the AI
+    /// lets go of the trigger for a frame after every pull before pulling it again, while
+    /// `FullAuto` weapons are just held down.
+    pub held: Vec<Entity>,
+}
+
+impl ShooterAI {
+    pub fn new(range: f32, reaction_delay: f32) -> Self {
+        Self {
+            range,
+            reaction_timer: Timer::from_seconds(reaction_delay, TimerMode::Once),
+            spotted: None,
+            held: Vec::new(),
+        }
+    }
+}
+
+fn in_sight(
+    rapier_context: &RapierContext,
+    shooter: Entity,
+    from: Vec2,
+    target: Entity,
+    to: Vec2,
+    range: f32,
+) -> bool {
+    let offset = to - from;
+    let distance = offset.length();
+    if distance > range {
+        return false;
+    }
+    let Some(direction) = offset.try_normalize() else {
+        return true;
+    };
+    let filter = QueryFilter::new()
+        .exclude_sensors()
+        .exclude_rigid_body(shooter);
+    match rapier_context.cast_ray(from, direction, distance, true, filter) {
+        Some((hit, _)) => hit == target,
+        None => true,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn do_shooter_ai(
+    mut shooters: Query<
+        (
+            Entity,
+            &Actor,
+            &mut ShooterAI,
+            &Children,
+            Option<&WeaponInventory>,
+        ),
+        Without<Player>,
+    >,
+    transforms: Query<&Transform2d>,
+    weapons: Query<(&Weapon, Option<&ChargeState>)>,
+    mut fire_events: EventWriter<FireWeaponEvent>,
+    mut start_charge_events: EventWriter<StartChargeEvent>,
+    mut release_charge_events: EventWriter<ReleaseChargeEvent>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    for (shooter, actor, mut ai, children, inventory) in shooters.iter_mut() {
+        let visible = actor.desired_target.filter(|target| {
+            match (transforms.get(shooter), transforms.get(*target)) {
+                (Ok(from), Ok(to)) => in_sight(
+                    &rapier_context,
+                    shooter,
+                    from.translation,
+                    *target,
+                    to.translation,
+                    ai.range,
+                ),
+                _ => false,
+            }
+        });
+        if visible != ai.spotted {
+            ai.spotted = visible;
+            ai.reaction_timer.reset();
+        }
+        let Some(target) = visible else {
+            ai.held.clear();
+            continue;
+        };
+        ai.reaction_timer.tick(time.delta());
+        if !ai.reaction_timer.finished() {
+            continue;
+        }
+
+        let armed = match inventory {
+            Some(inventory) => inventory.ready_weapon().into_iter().collect(),
+            None => children.to_vec(),
+        };
+        for weapon in armed {
+            let Ok((weapon_component, charge)) = weapons.get(weapon) else {
+                continue;
+            };
+            match (weapon_component.fire_mode, charge) {
+                (WeaponFireMode::Charge { max_charge, .. }, Some(charge)) if charge.charging => {
+                    if charge.elapsed >= max_charge {
+                        release_charge_events.send(ReleaseChargeEvent {
+                            weapon,
+                            action: WeaponAction::Primary,
+                        });
+                    }
+                }
+                (WeaponFireMode::Charge { .. }, _) => {
+                    if weapon_component.can_fire {
+                        start_charge_events.send(StartChargeEvent {
+                            weapon,
+                            target: Some(target),
+                            action: WeaponAction::Primary,
+                        });
+                    }
+                }
+                (fire_mode, _) => {
+                    let semi_auto = fire_mode != WeaponFireMode::FullAuto;
+                    if semi_auto && ai.held.contains(&weapon) {
+                        ai.held.retain(|held| *held != weapon);
+                        continue;
+                    }
+                    if weapon_component.can_fire {
+                        fire_events.send(FireWeaponEvent {
+                            weapon,
+                            target: Some(target),
+                            action: WeaponAction::Primary,
+                        });
+                        if semi_auto {
+                            ai.held.push(weapon);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::event::ManualEventReader,
+        prelude::{App, BuildWorldChildren, Events, Update},
+        MinimalPlugins,
+    };
+
+    use super::*;
+
+    /// Frames on which the AI pulled the trigger of a weapon with `fire_mode` over `frames`
+    /// updates. Nothing fires the weapon, so it never goes on cooldown.
+    fn pulls(fire_mode: WeaponFireMode, frames: usize) -> Vec<usize> {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<RapierContext>()
+            .add_event::<FireWeaponEvent>()
+            .add_event::<StartChargeEvent>()
+            .add_event::<ReleaseChargeEvent>()
+            .add_systems(Update, do_shooter_ai);
+        let world = app.world_mut();
+        let target = world
+            .spawn(Transform2d::from_translation(Vec2::new(50., 0.)))
+            .id();
+        let weapon = world
+            .spawn(Weapon {
+                can_fire: true,
+                fire_func: Box::new(|_| ()),
+                fire_mode,
+            })
+            .id();
+        let actor = Actor {
+            desired_target: Some(target),
+            ..Actor::default()
+        };
+        world
+            .spawn((actor, ShooterAI::new(100., 0.), Transform2d::default()))
+            .push_children(&[weapon]);
+
+        let mut reader = ManualEventReader::<FireWeaponEvent>::default();
+        (0..frames)
+            .filter(|_| {
+                app.update();
+                let events = app.world().resource::<Events<FireWeaponEvent>>();
+                reader.read(events).count() > 0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn semi_auto_trigger_is_released_between_pulls() {
+        assert_eq!(pulls(WeaponFireMode::SemiAuto, 5), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn full_auto_trigger_is_held_down() {
+        assert_eq!(pulls(WeaponFireMode::FullAuto, 5), vec![0, 1, 2, 3, 4]);
+    }
+}
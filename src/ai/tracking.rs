@@ -1,5 +1,5 @@
 use bevy::prelude::Reflect;
-use bevy::prelude::{Component, Query, With};
+use bevy::prelude::{Component, Entity, Query, With};
 use bevy_mod_transform2d::transform2d::Transform2d;
 
 use crate::{actors::Actor, player::Player};
@@ -9,13 +9,61 @@ pub struct TrackerAI {
     pub precision: f32,
 }
 
+/// Walks toward the player. The player also becomes the actor's `desired_target`, unless it already
+/// has a target that's still around.
 pub(crate) fn do_tracker_ai(
-    player: Query<&Transform2d, With<Player>>,
+    player: Query<(Entity, &Transform2d), With<Player>>,
     mut ais: Query<(&mut Actor, &Transform2d, &TrackerAI)>,
+    targets: Query<(), With<Transform2d>>,
 ) {
-    let player_pos = player.single().translation;
+    let (player, player_transform) = player.single();
+    let player_pos = player_transform.translation;
     for (mut enemy, transform, tracker) in ais.iter_mut() {
+        if !enemy
+            .desired_target
+            .is_some_and(|target| targets.contains(target))
+        {
+            enemy.desired_target = Some(player);
+        }
         enemy.desired_direction +=
             tracker.precision * (player_pos - transform.translation).clamp_length_max(1.);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{App, Update},
+        MinimalPlugins,
+    };
+
+    use super::*;
+
+    #[test]
+    fn existing_targets_are_kept() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems(Update, do_tracker_ai);
+        let world = app.world_mut();
+        let player = world.spawn((Player, Transform2d::default())).id();
+        let decoy = world.spawn(Transform2d::default()).id();
+        let tracker = TrackerAI { precision: 1. };
+        let mut spawn_tracker = |target| {
+            let actor = Actor {
+                desired_target: target,
+                ..Actor::default()
+            };
+            world.spawn((actor, Transform2d::default(), tracker)).id()
+        };
+        let (idle, busy) = (spawn_tracker(None), spawn_tracker(Some(decoy)));
+
+        app.update();
+        let target = |app: &App, entity| app.world().get::<Actor>(entity).unwrap().desired_target;
+        assert_eq!(target(&app, idle), Some(player));
+        assert_eq!(target(&app, busy), Some(decoy));
+
+        app.world_mut().despawn(decoy);
+        app.update();
+        assert_eq!(target(&app, busy), Some(player));
+    }
+}
@@ -10,11 +10,12 @@ use bevy::{
 use bevy_mod_transform2d::{transform2d::Transform2d, Transform2dPlugin};
 use std::marker::PhantomData;
 
-use bevy_rapier2d::prelude::{NoUserData, RapierConfiguration, RapierPhysicsPlugin};
+use bevy_rapier2d::prelude::{RapierConfiguration, RapierPhysicsPlugin};
 use bevy_turborand::prelude::RngPlugin;
 use camera::CameraPlugin;
 use meta_states::PluginControlState;
-use stats::{Health, Knockback, Speed};
+use projectile::ProjectilePhysicsHooks;
+use stats::{Damage, Health, Knockback, Speed};
 
 pub use self::{
     actors::ActorPlugin, ai::AIPlugin, meta_states::DummyStates, player::PlayerPlugin,
@@ -28,6 +29,8 @@ pub mod meta_states;
 pub mod player;
 pub mod projectile;
 pub mod stats;
+#[cfg(test)]
+mod testing;
 pub mod transform2d_mods;
 pub mod utils;
 pub mod weapons;
@@ -63,7 +66,7 @@ impl<T: PluginControlState + FreelyMutableState> Plugin for TwinStickToggleableP
     fn build(&self, app: &mut App) {
         app.init_state::<T>();
         app.add_plugins((
-            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.),
+            RapierPhysicsPlugin::<ProjectilePhysicsHooks>::pixels_per_meter(100.),
             RngPlugin::default(),
         ));
 
@@ -84,6 +87,7 @@ impl<T: PluginControlState + FreelyMutableState> Plugin for TwinStickToggleableP
         app.register_type::<Speed>();
         app.register_type::<Health>();
         app.register_type::<Knockback>();
+        app.register_type::<Damage>();
 
         app.add_systems(Startup, rapier_config_setup);
     }
@@ -4,14 +4,22 @@ use std::marker::PhantomData;
 use crate::{
     meta_states::PluginControlState,
     utils::*,
-    weapons::{FireWeaponEvent, Weapon, WeaponFireMode},
+    weapons::{
+        aim_assist::AimAssist,
+        ammo::ReloadWeaponEvent,
+        charge::{ReleaseChargeEvent, StartChargeEvent},
+        inventory::{SwitchWeaponEvent, WeaponInventory, WeaponSwitch},
+        pickup::{DropWeaponEvent, PickUpWeaponEvent},
+        FireWeaponEvent, SecondaryFire, Weapon, WeaponAction, WeaponFireMode, WeaponTriggerSet,
+    },
 };
 
 use bevy::{
-    input::ButtonInput,
+    input::{mouse::MouseWheel, ButtonInput},
     prelude::{
-        in_state, App, Camera2dBundle, Children, Commands, Component, Entity, EventWriter,
-        IntoSystemConfigs, MouseButton, Name, Plugin, Query, Res, Resource, Startup, Update, With,
+        in_state, App, Camera2dBundle, Children, Commands, Component, Entity, EventReader,
+        EventWriter, IntoSystemConfigs, KeyCode, MouseButton, Name, Plugin, Query, Res, Resource,
+        Startup, Update, With,
     },
     window::Window,
 };
@@ -32,6 +40,22 @@ pub struct Cursor(pub Entity);
 #[derive(Component, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
 pub struct Player;
 
+/// Mouse buttons that trigger the player's weapon actions.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub struct WeaponBindings {
+    pub primary: MouseButton,
+    pub secondary: MouseButton,
+}
+
+impl Default for WeaponBindings {
+    fn default() -> Self {
+        Self {
+            primary: MouseButton::Left,
+            secondary: MouseButton::Right,
+        }
+    }
+}
+
 #[derive(Default, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
 pub struct PlayerPlugin<T: PluginControlState> {
     _z: PhantomData<T>,
@@ -39,12 +63,19 @@ pub struct PlayerPlugin<T: PluginControlState> {
 
 impl<T: PluginControlState> Plugin for PlayerPlugin<T> {
     fn build(&self, app: &mut App) {
+        app.init_resource::<WeaponBindings>();
         app.add_systems(Startup, player_setup);
         app.add_systems(
             Update,
             (
                 update_cursor_tracker,
-                fire_player_weapons.run_if(player_exists),
+                fire_player_weapons
+                    .run_if(player_exists)
+                    .in_set(WeaponTriggerSet),
+                reload_player_weapons.run_if(player_exists),
+                switch_player_weapons.run_if(player_exists),
+                pick_up_player_weapons.run_if(player_exists),
+                drop_player_weapons.run_if(player_exists),
             )
                 .run_if(in_state(T::active_state())),
         );
@@ -93,26 +124,154 @@ pub fn player_exists(players: Query<(), With<Player>>) -> bool {
 
 pub fn fire_player_weapons(
     buttons: Res<ButtonInput<MouseButton>>,
+    bindings: Res<WeaponBindings>,
     mut events: EventWriter<FireWeaponEvent>,
-    weapons: Query<(Entity, &Weapon)>,
-    players_children_query: Query<&Children, With<Player>>,
+    mut start_charge_events: EventWriter<StartChargeEvent>,
+    mut release_charge_events: EventWriter<ReleaseChargeEvent>,
+    weapons: Query<(Entity, &Weapon, Option<&SecondaryFire>)>,
+    players_children_query: Query<
+        (&Children, Option<&WeaponInventory>, Option<&AimAssist>),
+        With<Player>,
+    >,
 ) {
-    for parent_player in players_children_query.iter() {
-        for &child in parent_player.iter() {
-            if let Ok((entity, weapon)) = weapons.get(child) {
-                let trigger_func = weapon.fire_mode;
-                if ((buttons.just_pressed(MouseButton::Left)
-                    && trigger_func == WeaponFireMode::SemiAuto)
-                    || (buttons.pressed(MouseButton::Left)
-                        && trigger_func == WeaponFireMode::FullAuto))
-                    && weapon.can_fire
-                {
+    let actions = [
+        (bindings.primary, WeaponAction::Primary),
+        (bindings.secondary, WeaponAction::Secondary),
+    ];
+    for (parent_player, inventory, assist) in players_children_query.iter() {
+        let target = assist.and_then(|a| a.target);
+        let triggerable = match inventory {
+            Some(inventory) => inventory.ready_weapon().into_iter().collect(),
+            None => parent_player.to_vec(),
+        };
+        for child in triggerable {
+            let Ok((entity, weapon, secondary)) = weapons.get(child) else {
+                continue;
+            };
+            for (button, action) in actions {
+                let Some((fire_mode, can_fire)) = weapon.action(secondary, action) else {
+                    continue;
+                };
+                let triggered = match fire_mode {
+                    WeaponFireMode::SemiAuto | WeaponFireMode::Burst { .. } => {
+                        buttons.just_pressed(button)
+                    }
+                    WeaponFireMode::FullAuto => buttons.pressed(button),
+                    WeaponFireMode::Charge { .. } => {
+                        if buttons.just_pressed(button) {
+                            start_charge_events.send(StartChargeEvent {
+                                weapon: entity,
+                                target,
+                                action,
+                            });
+                        }
+                        if buttons.just_released(button) {
+                            release_charge_events.send(ReleaseChargeEvent {
+                                weapon: entity,
+                                action,
+                            });
+                        }
+                        false
+                    }
+                };
+                if triggered && can_fire {
                     events.send(FireWeaponEvent {
                         weapon: entity,
-                        target: None,
+                        target,
+                        action,
                     });
                 }
             }
         }
     }
 }
+
+pub fn reload_player_weapons(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut events: EventWriter<ReloadWeaponEvent>,
+    weapons: Query<Entity, With<Weapon>>,
+    players_children_query: Query<(&Children, Option<&WeaponInventory>), With<Player>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    for (parent_player, inventory) in players_children_query.iter() {
+        let reloadable = match inventory {
+            Some(inventory) => inventory.active_weapon().into_iter().collect(),
+            None => parent_player.to_vec(),
+        };
+        for child in reloadable {
+            if let Ok(weapon) = weapons.get(child) {
+                events.send(ReloadWeaponEvent { weapon });
+            }
+        }
+    }
+}
+
+pub fn pick_up_player_weapons(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut events: EventWriter<PickUpWeaponEvent>,
+    players: Query<Entity, With<Player>>,
+) {
+    if keys.just_pressed(KeyCode::KeyF) {
+        for actor in players.iter() {
+            events.send(PickUpWeaponEvent { actor });
+        }
+    }
+}
+
+pub fn drop_player_weapons(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut events: EventWriter<DropWeaponEvent>,
+    weapons: Query<Entity, With<Weapon>>,
+    players_children_query: Query<(&Children, Option<&WeaponInventory>), With<Player>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+    for (parent_player, inventory) in players_children_query.iter() {
+        let dropped = match inventory {
+            Some(inventory) => inventory.active_weapon(),
+            None => parent_player.iter().copied().find(|c| weapons.contains(*c)),
+        };
+        if let Some(weapon) = dropped {
+            events.send(DropWeaponEvent { weapon });
+        }
+    }
+}
+
+const SLOT_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+pub fn switch_player_weapons(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut events: EventWriter<SwitchWeaponEvent>,
+    players: Query<Entity, (With<Player>, With<WeaponInventory>)>,
+) {
+    let scroll: f32 = wheel.read().map(|w| w.y).sum();
+    let switch = if keys.just_pressed(KeyCode::KeyE) || scroll < 0. {
+        Some(WeaponSwitch::Next)
+    } else if keys.just_pressed(KeyCode::KeyQ) || scroll > 0. {
+        Some(WeaponSwitch::Previous)
+    } else {
+        SLOT_KEYS
+            .iter()
+            .position(|key| keys.just_pressed(*key))
+            .map(WeaponSwitch::Slot)
+    };
+    if let Some(switch) = switch {
+        for actor in players.iter() {
+            events.send(SwitchWeaponEvent { actor, switch });
+        }
+    }
+}
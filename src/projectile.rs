@@ -1,21 +1,31 @@
 use bevy::{
+    ecs::{
+        component::{ComponentHooks, StorageType},
+        system::SystemParam,
+    },
     prelude::{
         in_state, App, Bundle, Commands, Component, DespawnRecursiveExt, Entity, Event,
         EventReader, EventWriter, GlobalTransform, InheritedVisibility, IntoSystemConfigs, Plugin,
-        Query, Reflect, Res, Transform, Update, Vec2, Visibility,
+        Query, Reflect, Res, Transform, Update, Vec2, Visibility, With, Without,
     },
     time::{Time, Timer, TimerMode},
 };
 use bevy_mod_transform2d::transform2d::Transform2d;
 use bevy_rapier2d::{
-    pipeline::CollisionEvent,
+    pipeline::{BevyPhysicsHooks, CollisionEvent, PairFilterContextView, SolverFlags},
     prelude::{
-        ActiveEvents, Collider, ColliderMassProperties, ExternalImpulse, RigidBody, Velocity,
+        ActiveEvents, ActiveHooks, Collider, ColliderMassProperties, ExternalImpulse, QueryFilter,
+        RapierContext, RigidBody, Sensor, Velocity,
     },
 };
+use serde::Deserialize;
 use std::{marker::PhantomData, time::Duration};
 
-use crate::meta_states::PluginControlState;
+use crate::{
+    actors::{Actor, Faction, Hittable},
+    meta_states::PluginControlState,
+    stats::{Damage, DamageDealt, Health},
+};
 
 #[derive(Component, Clone, PartialEq, Eq, Reflect, Debug)]
 pub struct Lifespan(Timer);
@@ -32,18 +42,49 @@ impl Default for Lifespan {
     }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Reflect, Debug)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Reflect, Debug, Deserialize)]
 pub enum ProjectileImpactBehavior {
     Die,
+    /// Ricochet off whatever was hit, within the projectile's `Bounce` budget.
     Bounce,
+    /// Fly on through. Piercing projectiles are sensors, so they keep their velocity; give them
+    /// a `Pierce` to limit how many actors they pass through.
+    Pierce,
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug)]
 pub struct Projectile {
+    /// What happens when the projectile hits an actor, i.e. anything with `Actor` or `Health`.
     pub on_hit: ProjectileImpactBehavior,
+    /// What happens when the projectile hits anything else, like walls and props.
     pub on_impact: ProjectileImpactBehavior,
 }
 
+impl Component for Projectile {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    /// Piercing projectiles are made sensors as soon as they're spawned, however they're spawned,
+    /// so they never spend a physics step as solid bodies. They get an unlimited `Pierce` unless
+    /// they were spawned with one.
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_add(|mut world, entity, _| {
+            let Some(projectile) = world.get::<Projectile>(entity) else {
+                return;
+            };
+            if projectile.on_hit != ProjectileImpactBehavior::Pierce {
+                return;
+            }
+            let has_pierce = world.get::<Pierce>(entity).is_some();
+            let mut commands = world.commands();
+            let mut projectile = commands.entity(entity);
+            projectile.insert(Sensor);
+            if !has_pierce {
+                projectile.insert(Pierce::new(u32::MAX));
+            }
+        });
+    }
+}
+
 impl Default for Projectile {
     fn default() -> Self {
         Self {
@@ -56,6 +97,124 @@ impl Default for Projectile {
 #[derive(Component, Clone, Copy, PartialEq, Reflect, Debug)]
 pub struct Knockback(pub f32);
 
+/// The actor that fired a projectile. Projectiles pass through their shooter, and through its
+/// allies when they share its `Faction`: they neither hit them nor push them around. The physics
+/// side of that is up to `ProjectilePhysicsHooks`.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub struct Shooter(pub Entity);
+
+/// Whether a projectile fired by `shooter` with `faction` passes through `other`, because it is
+/// the shooter itself or an ally of the projectile.
+fn passes_through(
+    shooter: Option<&Shooter>,
+    faction: Option<&Faction>,
+    other: Entity,
+    other_faction: Option<&Faction>,
+) -> bool {
+    shooter.is_some_and(|s| s.0 == other)
+        || faction
+            .zip(other_faction)
+            .is_some_and(|(faction, other)| faction.is_allied_with(other))
+}
+
+/// Rapier physics hooks that drop every contact between a projectile and its `Shooter` or the
+/// shooter's allies, so a projectile fired from inside its shooter's collider flies out without
+/// either of them being pushed around, and allied projectiles fly through each other.
+/// `TwinStickPlugin` sets up Rapier with these; apps that add `RapierPhysicsPlugin` themselves
+/// should use `RapierPhysicsPlugin::<ProjectilePhysicsHooks>`. Projectiles need `ActiveHooks`
+/// for them, which `ProjectileBundle` includes.
+#[derive(SystemParam)]
+pub struct ProjectilePhysicsHooks<'w, 's> {
+    projectiles:
+        Query<'w, 's, (Option<&'static Shooter>, Option<&'static Faction>), With<Projectile>>,
+    factions: Query<'w, 's, &'static Faction>,
+}
+
+impl ProjectilePhysicsHooks<'_, '_> {
+    fn passes_through(&self, e1: Entity, e2: Entity) -> bool {
+        [(e1, e2), (e2, e1)].into_iter().any(|(projectile, other)| {
+            self.projectiles
+                .get(projectile)
+                .is_ok_and(|(shooter, faction)| {
+                    passes_through(shooter, faction, other, self.factions.get(other).ok())
+                })
+        })
+    }
+}
+
+impl BevyPhysicsHooks for ProjectilePhysicsHooks<'_, '_> {
+    fn filter_contact_pair(&self, context: PairFilterContextView) -> Option<SolverFlags> {
+        if self.passes_through(context.collider1(), context.collider2()) {
+            None
+        } else {
+            Some(SolverFlags::COMPUTE_IMPULSES)
+        }
+    }
+
+    fn filter_intersection_pair(&self, context: PairFilterContextView) -> bool {
+        !self.passes_through(context.collider1(), context.collider2())
+    }
+}
+
+/// Pierce budget of a projectile with `on_hit: Pierce`. Every actor it hits after the first uses
+/// up one of the `remaining` pierces, and the projectile dies on the hit it can't pay for, so a
+/// `Pierce::new(2)` hits up to three actors. Each actor is only ever hit once. Piercing
+/// projectiles spawned without a `Pierce` get one without a limit.
+#[derive(Component, Clone, PartialEq, Reflect, Debug)]
+pub struct Pierce {
+    pub remaining: u32,
+    /// Fraction of its `Damage` the projectile loses every time it pierces, from `0` to `1`.
+    pub falloff: f32,
+    pub hit: Vec<Entity>,
+}
+
+impl Pierce {
+    pub fn new(remaining: u32) -> Self {
+        Self {
+            remaining,
+            falloff: 0.,
+            hit: Vec::new(),
+        }
+    }
+
+    pub fn falloff(self, falloff: f32) -> Self {
+        Self { falloff, ..self }
+    }
+}
+
+/// Ricochet state of a projectile that bounces on hit or impact. Each bounce reflects the
+/// projectile's velocity off the contact normal and uses up one of the `remaining` bounces, and
+/// the projectile dies on the bounce it can't pay for. Projectiles that bounce without a `Bounce`
+/// get one without a limit.
+#[derive(Component, Clone, Copy, PartialEq, Reflect, Debug)]
+pub struct Bounce {
+    pub remaining: u32,
+    /// How far to look for a hostile actor in line of sight to turn toward after every bounce.
+    /// Hostility is judged by the projectile's own `Faction`, which weapons copy over from their
+    /// wielder, with projectiles without one counting as `FriendlyToAll`.
+    pub retarget_radius: Option<f32>,
+    /// Velocity before the last physics step, as the collision has already changed the current
+    /// one by the time it is reported.
+    velocity: Vec2,
+}
+
+impl Bounce {
+    pub fn new(remaining: u32) -> Self {
+        Self {
+            remaining,
+            retarget_radius: None,
+            velocity: Vec2::ZERO,
+        }
+    }
+
+    pub fn retarget(self, radius: f32) -> Self {
+        Self {
+            retarget_radius: Some(radius),
+            ..self
+        }
+    }
+}
+
 #[derive(Bundle, Clone, Debug)]
 pub struct ProjectileBundle {
     pub projectile: Projectile,
@@ -69,6 +228,8 @@ pub struct ProjectileBundle {
     pub mass_properties: ColliderMassProperties,
     pub collider: Collider,
     pub event_trigger: ActiveEvents,
+    /// Runs `ProjectilePhysicsHooks` on the projectile's contacts.
+    pub physics_hooks: ActiveHooks,
 }
 
 impl Default for ProjectileBundle {
@@ -85,23 +246,83 @@ impl Default for ProjectileBundle {
             mass_properties: ColliderMassProperties::Density(1.),
             collider: Collider::ball(5.),
             event_trigger: ActiveEvents::COLLISION_EVENTS,
+            physics_hooks: ActiveHooks::FILTER_CONTACT_PAIRS
+                | ActiveHooks::FILTER_INTERSECTION_PAIR,
         }
     }
 }
 
 #[derive(Event, Clone, Copy, PartialEq, Reflect, Debug)]
 pub struct KnockbackEvent {
-    entity: Entity,
-    direction: Vec2,
-    force: f32,
+    pub entity: Entity,
+    pub direction: Vec2,
+    pub force: f32,
 }
 
+/// A projectile ran into something, actor or not. Sent alongside a `ProjectileHitEvent` for
+/// actors and a `ProjectileGeometryImpactEvent` for everything else, so react to those two to tell
+/// the cases apart. Sensors, the projectile's `Shooter` and its allies, and actors a piercing
+/// projectile has already hit don't count.
 #[derive(Event, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
 pub struct ProjectileImpactEvent {
     pub projectile: Entity,
     pub impacted: Entity,
 }
 
+/// A projectile hit an actor, i.e. anything with `Actor` or `Health`. Its `on_hit` behavior
+/// applies.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub struct ProjectileHitEvent {
+    pub projectile: Entity,
+    pub target: Entity,
+}
+
+/// A projectile ran into something that isn't an actor, like a wall. Its `on_impact` behavior
+/// applies.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub struct ProjectileGeometryImpactEvent {
+    pub projectile: Entity,
+    pub impacted: Entity,
+}
+
+/// Steers a projectile toward `target` at up to `turn_rate` radians per second, keeping its
+/// speed. Without a target, or once the target is gone, it locks onto the nearest actor hostile
+/// to its `Faction` within `acquisition_radius`, with projectiles without a `Faction` counting as
+/// `FriendlyToAll`. A bouncing projectile that retargets locks onto its new target.
+#[derive(Component, Clone, Copy, PartialEq, Reflect, Debug)]
+pub struct Homing {
+    pub turn_rate: f32,
+    pub acquisition_radius: f32,
+    pub target: Option<Entity>,
+}
+
+impl Homing {
+    pub fn new(turn_rate: f32, acquisition_radius: f32) -> Self {
+        Self {
+            turn_rate,
+            acquisition_radius,
+            target: None,
+        }
+    }
+
+    /// Start out locked onto `target`, e.g. the `WeaponArguments::target` of the shot.
+    pub fn target(self, target: Option<Entity>) -> Self {
+        Self { target, ..self }
+    }
+}
+
+#[derive(Event, Clone, Copy, PartialEq, Reflect, Debug)]
+pub struct ProjectileBounceEvent {
+    pub projectile: Entity,
+    /// What the projectile bounced off.
+    pub surface: Entity,
+    /// Contact normal, pointing back toward where the projectile came from.
+    pub normal: Vec2,
+    pub position: Vec2,
+    /// The actor the projectile turned toward, if it retargets.
+    pub retargeted: Option<Entity>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
 pub struct ProjectileClashEvent(pub Entity, pub Entity);
 
@@ -122,12 +343,26 @@ impl<T: PluginControlState> Plugin for ProjectilePlugin<T> {
                 projectile_event_dispatcher,
                 kill_projectiles_post_impact,
                 knockback_from_projectiles,
+                damage_from_projectiles,
+                pierce_projectiles.after(damage_from_projectiles),
+                add_bounce_budgets,
+                (
+                    bounce_projectiles,
+                    steer_homing_projectiles,
+                    track_bounce_velocities,
+                )
+                    .chain()
+                    .after(projectile_event_dispatcher),
             )
                 .run_if(in_state(T::active_state())),
         );
 
         app.add_event::<ProjectileImpactEvent>()
-            .add_event::<ProjectileClashEvent>();
+            .add_event::<ProjectileHitEvent>()
+            .add_event::<ProjectileGeometryImpactEvent>()
+            .add_event::<ProjectileBounceEvent>()
+            .add_event::<ProjectileClashEvent>()
+            .add_event::<DamageDealt>();
     }
 }
 
@@ -145,32 +380,61 @@ fn tick_lifetimes(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn projectile_event_dispatcher(
     mut collision_events: EventReader<CollisionEvent>,
     projectile_query: Query<&Projectile>,
+    owners: Query<(Option<&Shooter>, Option<&Faction>), With<Projectile>>,
+    mut pierces: Query<&mut Pierce>,
+    actors: Query<(), Hittable>,
+    factions: Query<&Faction>,
+    sensors: Query<(), With<Sensor>>,
     mut projectile_events: EventWriter<ProjectileImpactEvent>,
+    mut hit_events: EventWriter<ProjectileHitEvent>,
+    mut geometry_events: EventWriter<ProjectileGeometryImpactEvent>,
     mut clash_events: EventWriter<ProjectileClashEvent>,
 ) {
     for collision_event in collision_events.read() {
         if let CollisionEvent::Started(e1, e2, _) = collision_event {
-            match (projectile_query.get(*e1), projectile_query.get(*e2)) {
+            let (projectile, other) = match (projectile_query.get(*e1), projectile_query.get(*e2)) {
                 (Ok(_), Ok(_)) => {
                     clash_events.send(ProjectileClashEvent(*e1, *e2));
+                    continue;
                 }
-                (Ok(_), _) => {
-                    projectile_events.send(ProjectileImpactEvent {
-                        projectile: *e1,
-                        impacted: *e2,
-                    });
-                }
-                (Err(_), Ok(_)) => {
-                    projectile_events.send(ProjectileImpactEvent {
-                        impacted: *e1,
-                        projectile: *e2,
-                    });
-                }
+                (Ok(_), _) => (*e1, *e2),
+                (Err(_), Ok(_)) => (*e2, *e1),
                 (Err(_), Err(_)) => continue,
             };
+            // Trigger volumes like weapon pickups aren't something to hit.
+            if sensors.contains(other) {
+                continue;
+            }
+            if let Ok((shooter, faction)) = owners.get(projectile) {
+                if passes_through(shooter, faction, other, factions.get(other).ok()) {
+                    continue;
+                }
+            }
+            if actors.contains(other) {
+                if let Ok(mut pierce) = pierces.get_mut(projectile) {
+                    if pierce.hit.contains(&other) {
+                        continue;
+                    }
+                    pierce.hit.push(other);
+                }
+                hit_events.send(ProjectileHitEvent {
+                    projectile,
+                    target: other,
+                });
+            } else {
+                geometry_events.send(ProjectileGeometryImpactEvent {
+                    projectile,
+                    impacted: other,
+                });
+            }
+            projectile_events.send(ProjectileImpactEvent {
+                projectile,
+                impacted: other,
+            });
         }
     }
 }
@@ -201,25 +465,262 @@ fn knockback_from_projectiles(
     }
 }
 
+fn damage_from_projectiles(
+    mut hit_events: EventReader<ProjectileHitEvent>,
+    mut damage_events: EventWriter<DamageDealt>,
+    projectiles: Query<(&Damage, &Transform2d)>,
+    mut health: Query<&mut Health>,
+) {
+    for ProjectileHitEvent { projectile, target } in hit_events.read() {
+        let (Ok((Damage(damage), transform)), Ok(mut health)) =
+            (projectiles.get(*projectile), health.get_mut(*target))
+        else {
+            continue;
+        };
+        health.0 -= damage;
+        damage_events.send(DamageDealt {
+            source: *projectile,
+            target: *target,
+            amount: *damage,
+            position: transform.translation,
+        });
+    }
+}
+
 fn kill_projectiles_post_impact(
-    mut events: EventReader<ProjectileImpactEvent>,
+    mut hit_events: EventReader<ProjectileHitEvent>,
+    mut impact_events: EventReader<ProjectileGeometryImpactEvent>,
     mut commands: Commands,
     query: Query<&Projectile>,
 ) {
-    for ProjectileImpactEvent {
-        projectile: projectile_id,
-        impacted: _,
-    } in events.read()
-    {
-        let proj = query.get(*projectile_id);
-        match proj {
-            Ok(projectile) => {
-                if projectile.on_impact == ProjectileImpactBehavior::Die {
-                    commands.entity(*projectile_id).despawn_recursive();
-                }
+    let hits = hit_events
+        .read()
+        .filter_map(|e| Some((e.projectile, query.get(e.projectile).ok()?.on_hit)));
+    let impacts = impact_events
+        .read()
+        .filter_map(|e| Some((e.projectile, query.get(e.projectile).ok()?.on_impact)));
+    for (projectile, behavior) in hits.chain(impacts) {
+        if behavior == ProjectileImpactBehavior::Die {
+            commands.entity(projectile).despawn_recursive();
+        }
+    }
+}
+
+fn pierce_projectiles(
+    mut hit_events: EventReader<ProjectileHitEvent>,
+    mut commands: Commands,
+    mut projectiles: Query<(&Projectile, &mut Pierce, Option<&mut Damage>)>,
+) {
+    for ProjectileHitEvent { projectile, .. } in hit_events.read() {
+        let Ok((behavior, mut pierce, damage)) = projectiles.get_mut(*projectile) else {
+            continue;
+        };
+        if behavior.on_hit != ProjectileImpactBehavior::Pierce {
+            continue;
+        }
+        if pierce.remaining == 0 {
+            commands.entity(*projectile).despawn_recursive();
+            continue;
+        }
+        pierce.remaining -= 1;
+        if let Some(mut damage) = damage {
+            damage.0 *= 1. - pierce.falloff.clamp(0., 1.);
+        }
+    }
+}
+
+fn add_bounce_budgets(
+    mut commands: Commands,
+    projectiles: Query<(Entity, &Projectile, &Velocity), Without<Bounce>>,
+) {
+    for (entity, projectile, velocity) in projectiles.iter() {
+        if projectile.on_hit == ProjectileImpactBehavior::Bounce
+            || projectile.on_impact == ProjectileImpactBehavior::Bounce
+        {
+            commands.entity(entity).insert(Bounce {
+                velocity: velocity.linvel,
+                ..Bounce::new(u32::MAX)
+            });
+        }
+    }
+}
+
+/// Mirrors `incoming` off a surface with the given `normal`, whichever side of the surface the
+/// normal points out of.
+fn reflect(incoming: Vec2, normal: Vec2) -> Vec2 {
+    let normal = if normal.dot(incoming) > 0. {
+        -normal
+    } else {
+        normal
+    };
+    incoming - 2. * incoming.dot(normal) * normal
+}
+
+/// Whether a ray from `projectile` at `position` reaches `target` without running into anything
+/// solid first.
+fn in_line_of_sight(
+    rapier_context: &RapierContext,
+    projectile: Entity,
+    position: Vec2,
+    target: Entity,
+    offset: Vec2,
+) -> bool {
+    let filter = QueryFilter::new()
+        .exclude_sensors()
+        .exclude_collider(projectile)
+        .exclude_rigid_body(projectile);
+    match rapier_context.cast_ray(position, offset, 1., true, filter) {
+        Some((hit, _)) => hit == target,
+        None => true,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn bounce_projectiles(
+    mut hit_events: EventReader<ProjectileHitEvent>,
+    mut impact_events: EventReader<ProjectileGeometryImpactEvent>,
+    mut bounce_events: EventWriter<ProjectileBounceEvent>,
+    mut commands: Commands,
+    mut projectiles: Query<(
+        &Projectile,
+        &mut Bounce,
+        &mut Velocity,
+        &mut Transform2d,
+        Option<&Faction>,
+        Option<&mut Homing>,
+    )>,
+    candidates: Query<(Entity, &Transform2d, &Faction), (With<Actor>, Without<Projectile>)>,
+    surfaces: Query<&Transform2d, Without<Projectile>>,
+    rapier_context: Res<RapierContext>,
+) {
+    let hits = hit_events.read().map(|e| (e.projectile, e.target, true));
+    let impacts = impact_events
+        .read()
+        .map(|e| (e.projectile, e.impacted, false));
+    for (entity, surface, is_hit) in hits.chain(impacts) {
+        let Ok((projectile, mut bounce, mut velocity, mut transform, faction, homing)) =
+            projectiles.get_mut(entity)
+        else {
+            continue;
+        };
+        let behavior = if is_hit {
+            projectile.on_hit
+        } else {
+            projectile.on_impact
+        };
+        if behavior != ProjectileImpactBehavior::Bounce {
+            continue;
+        }
+        if bounce.remaining == 0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        bounce.remaining -= 1;
+
+        let position = transform.translation;
+        let incoming = if bounce.velocity == Vec2::ZERO {
+            velocity.linvel
+        } else {
+            bounce.velocity
+        };
+        // Sensors, like piercing projectiles, have no contacts, so fall back to pushing off the
+        // surface's center.
+        let normal = rapier_context
+            .contact_pair(entity, surface)
+            .and_then(|pair| pair.manifolds().find(|m| m.num_points() > 0))
+            .map(|manifold| manifold.normal())
+            .or_else(|| Some(position - surfaces.get(surface).ok()?.translation))
+            .and_then(Vec2::try_normalize)
+            .unwrap_or(-incoming.normalize_or_zero());
+        let normal = if normal.dot(incoming) > 0. {
+            -normal
+        } else {
+            normal
+        };
+        let mut outgoing = reflect(incoming, normal);
+
+        let faction = faction.copied().unwrap_or(Faction::FriendlyToAll);
+        let retargeted = bounce.retarget_radius.and_then(|radius| {
+            candidates
+                .iter()
+                .filter(|(e, _, other)| *e != surface && faction.is_hostile_to(other))
+                .map(|(e, t, _)| (e, t.translation - position))
+                .filter(|(_, offset)| offset.length() <= radius)
+                .filter(|(e, offset)| {
+                    in_line_of_sight(&rapier_context, entity, position, *e, *offset)
+                })
+                .min_by(|(_, a), (_, b)| a.length().total_cmp(&b.length()))
+        });
+        if let Some((target, offset)) = retargeted {
+            outgoing = offset.normalize_or_zero() * outgoing.length();
+            if let Some(mut homing) = homing {
+                homing.target = Some(target);
             }
-            Err(_) => (),
         }
+
+        velocity.linvel = outgoing;
+        bounce.velocity = outgoing;
+        if outgoing != Vec2::ZERO {
+            transform.rotation = outgoing.to_angle();
+        }
+        bounce_events.send(ProjectileBounceEvent {
+            projectile: entity,
+            surface,
+            normal,
+            position,
+            retargeted: retargeted.map(|(e, _)| e),
+        });
+    }
+}
+
+fn track_bounce_velocities(mut projectiles: Query<(&mut Bounce, &Velocity)>) {
+    for (mut bounce, velocity) in projectiles.iter_mut() {
+        bounce.velocity = velocity.linvel;
+    }
+}
+
+fn steer_homing_projectiles(
+    time: Res<Time>,
+    mut projectiles: Query<(
+        &mut Homing,
+        &mut Velocity,
+        &mut Transform2d,
+        Option<&Faction>,
+    )>,
+    candidates: Query<(Entity, &Transform2d, &Faction), (With<Actor>, Without<Homing>)>,
+    targets: Query<&Transform2d, Without<Homing>>,
+) {
+    for (mut homing, mut velocity, mut transform, faction) in projectiles.iter_mut() {
+        let position = transform.translation;
+        if homing
+            .target
+            .is_some_and(|target| !targets.contains(target))
+        {
+            homing.target = None;
+        }
+        if homing.target.is_none() {
+            let faction = faction.copied().unwrap_or(Faction::FriendlyToAll);
+            homing.target = candidates
+                .iter()
+                .filter(|(_, _, other)| faction.is_hostile_to(other))
+                .map(|(e, t, _)| (e, t.translation.distance(position)))
+                .filter(|(_, distance)| *distance <= homing.acquisition_radius)
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(e, _)| e);
+        }
+        let Some(target) = homing.target.and_then(|t| targets.get(t).ok()) else {
+            continue;
+        };
+        let max_turn = homing.turn_rate * time.delta_seconds();
+        let turn = velocity
+            .linvel
+            .angle_between(target.translation - position)
+            .clamp(-max_turn, max_turn);
+        if turn.is_nan() {
+            continue;
+        }
+        velocity.linvel = Vec2::from_angle(turn).rotate(velocity.linvel);
+        transform.rotation = velocity.linvel.to_angle();
     }
 }
 
@@ -239,3 +740,328 @@ fn knockback_events(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::event::ManualEventReader,
+        prelude::{AppExtStates, Events},
+        state::app::StatesPlugin,
+        time::TimeUpdateStrategy,
+        MinimalPlugins,
+    };
+    use bevy_rapier2d::rapier::prelude::CollisionEventFlags;
+
+    use super::*;
+    use crate::{
+        meta_states::DummyStates,
+        testing::{physics_app, sent},
+    };
+
+    const FRAME: Duration = Duration::from_millis(125);
+
+    /// Runs the projectile systems without physics. Collisions are sent by hand with `collide`.
+    fn projectile_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            ProjectilePlugin::<DummyStates>::default(),
+        ))
+        .init_state::<DummyStates>()
+        .init_resource::<RapierContext>()
+        .add_event::<CollisionEvent>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+        // The first update has no delta time.
+        app.update();
+        app
+    }
+
+    fn collide(app: &mut App, projectile: Entity, other: Entity) {
+        let flags = CollisionEventFlags::empty();
+        app.world_mut()
+            .send_event(CollisionEvent::Started(projectile, other, flags));
+    }
+
+    fn projectile(app: &mut App, projectile: Projectile) -> Entity {
+        let velocity = Velocity::linear(Vec2::new(100., 0.));
+        let transform = Transform2d::default();
+        app.world_mut()
+            .spawn((projectile, velocity, transform))
+            .id()
+    }
+
+    fn actor(app: &mut App, translation: Vec2, faction: Faction) -> Entity {
+        let transform = Transform2d::from_translation(translation);
+        app.world_mut()
+            .spawn((Actor::default(), Health(100.), faction, transform))
+            .id()
+    }
+
+    fn wall(app: &mut App, translation: Vec2) -> Entity {
+        let transform = Transform2d::from_translation(translation);
+        app.world_mut().spawn(transform).id()
+    }
+
+    fn health(app: &App, entity: Entity) -> f32 {
+        app.world().get::<Health>(entity).unwrap().0
+    }
+
+    #[test]
+    fn impacts_cover_actors_and_geometry() {
+        let mut app = projectile_app();
+        let mut impacts = ManualEventReader::<ProjectileImpactEvent>::default();
+        let mut hits = ManualEventReader::<ProjectileHitEvent>::default();
+        let mut geometry = ManualEventReader::<ProjectileGeometryImpactEvent>::default();
+        let (bullet, slug) = (
+            projectile(&mut app, Projectile::default()),
+            projectile(&mut app, Projectile::default()),
+        );
+        let target = actor(&mut app, Vec2::new(10., 0.), Faction::FactionID(1));
+        let wall = wall(&mut app, Vec2::new(-10., 0.));
+        collide(&mut app, bullet, target);
+        collide(&mut app, wall, slug);
+        app.update();
+
+        assert_eq!(
+            sent(&app, &mut impacts),
+            vec![
+                ProjectileImpactEvent {
+                    projectile: bullet,
+                    impacted: target,
+                },
+                ProjectileImpactEvent {
+                    projectile: slug,
+                    impacted: wall,
+                },
+            ]
+        );
+        assert_eq!(
+            sent(&app, &mut hits),
+            vec![ProjectileHitEvent {
+                projectile: bullet,
+                target,
+            }]
+        );
+        assert_eq!(
+            sent(&app, &mut geometry),
+            vec![ProjectileGeometryImpactEvent {
+                projectile: slug,
+                impacted: wall,
+            }]
+        );
+        assert!(app.world().get_entity(bullet).is_none());
+        assert!(app.world().get_entity(slug).is_none());
+    }
+
+    #[test]
+    fn shooter_and_allies_are_passed_through() {
+        let mut app = projectile_app();
+        let mut impacts = ManualEventReader::<ProjectileImpactEvent>::default();
+        let shooter = actor(&mut app, Vec2::ZERO, Faction::FactionID(1));
+        let ally = actor(&mut app, Vec2::new(10., 0.), Faction::FactionID(1));
+        let stranger = actor(&mut app, Vec2::new(20., 0.), Faction::FactionID(2));
+        let bullet = projectile(&mut app, Projectile::default());
+        app.world_mut().entity_mut(bullet).insert((
+            Shooter(shooter),
+            Faction::FactionID(1),
+            Damage(10.),
+        ));
+        collide(&mut app, bullet, shooter);
+        collide(&mut app, ally, bullet);
+        app.update();
+        assert!(sent(&app, &mut impacts).is_empty());
+
+        collide(&mut app, bullet, stranger);
+        app.update();
+        assert_eq!(sent(&app, &mut impacts).len(), 1);
+        assert_eq!(health(&app, shooter), 100.);
+        assert_eq!(health(&app, ally), 100.);
+        assert_eq!(health(&app, stranger), 90.);
+    }
+
+    #[test]
+    fn piercing_projectiles_are_sensors_from_the_start() {
+        let mut app = projectile_app();
+        let piercing = Projectile {
+            on_hit: ProjectileImpactBehavior::Pierce,
+            ..Projectile::default()
+        };
+        let unlimited = projectile(&mut app, piercing);
+        let limited = app.world_mut().spawn((Pierce::new(2), piercing)).id();
+        let plain = projectile(&mut app, Projectile::default());
+        app.update();
+
+        let world = app.world();
+        assert!(world.get::<Sensor>(unlimited).is_some());
+        assert_eq!(world.get::<Pierce>(unlimited).unwrap().remaining, u32::MAX);
+        assert!(world.get::<Sensor>(limited).is_some());
+        assert_eq!(world.get::<Pierce>(limited).unwrap().remaining, 2);
+        assert!(world.get::<Sensor>(plain).is_none());
+        assert!(world.get::<Pierce>(plain).is_none());
+    }
+
+    #[test]
+    fn pierce_budget_hits_each_actor_once_and_runs_out() {
+        let mut app = projectile_app();
+        let piercing = Projectile {
+            on_hit: ProjectileImpactBehavior::Pierce,
+            ..Projectile::default()
+        };
+        let spear = projectile(&mut app, piercing);
+        app.world_mut()
+            .entity_mut(spear)
+            .insert((Pierce::new(1).falloff(0.5), Damage(10.)));
+        let first = actor(&mut app, Vec2::new(10., 0.), Faction::FactionID(1));
+        let second = actor(&mut app, Vec2::new(20., 0.), Faction::FactionID(1));
+
+        collide(&mut app, spear, first);
+        collide(&mut app, first, spear);
+        app.update();
+        assert_eq!(health(&app, first), 90.);
+        assert_eq!(app.world().get::<Pierce>(spear).unwrap().remaining, 0);
+        assert_eq!(app.world().get::<Damage>(spear).unwrap().0, 5.);
+
+        collide(&mut app, spear, second);
+        app.update();
+        assert_eq!(health(&app, second), 95.);
+        assert!(app.world().get_entity(spear).is_none());
+    }
+
+    #[test]
+    fn reflection_mirrors_off_either_side_of_a_surface() {
+        let incoming = Vec2::new(1., -1.);
+        assert_eq!(reflect(incoming, Vec2::Y), Vec2::new(1., 1.));
+        assert_eq!(reflect(incoming, Vec2::NEG_Y), Vec2::new(1., 1.));
+        assert_eq!(reflect(Vec2::X, Vec2::NEG_X), Vec2::NEG_X);
+    }
+
+    #[test]
+    fn bounce_budget_runs_out() {
+        let mut app = projectile_app();
+        let bouncy = Projectile {
+            on_impact: ProjectileImpactBehavior::Bounce,
+            ..Projectile::default()
+        };
+        let ball = projectile(&mut app, bouncy);
+        app.world_mut().entity_mut(ball).insert(Bounce::new(1));
+        let wall = wall(&mut app, Vec2::new(10., 0.));
+
+        collide(&mut app, ball, wall);
+        app.update();
+        let velocity = app.world().get::<Velocity>(ball).unwrap().linvel;
+        assert_eq!(velocity, Vec2::new(-100., 0.));
+        assert_eq!(app.world().get::<Bounce>(ball).unwrap().remaining, 0);
+
+        collide(&mut app, ball, wall);
+        app.update();
+        assert!(app.world().get_entity(ball).is_none());
+    }
+
+    #[test]
+    fn retargeting_bounce_locks_homing_on() {
+        let mut app = projectile_app();
+        let bouncy = Projectile {
+            on_impact: ProjectileImpactBehavior::Bounce,
+            ..Projectile::default()
+        };
+        let ball = projectile(&mut app, bouncy);
+        // A homing projectile that can't turn or acquire targets on its own.
+        let homing = Homing::new(0., 0.);
+        app.world_mut()
+            .entity_mut(ball)
+            .insert((Bounce::new(3).retarget(500.), homing));
+        let wall = wall(&mut app, Vec2::new(10., 0.));
+        let enemy = actor(&mut app, Vec2::new(0., 50.), Faction::FactionID(1));
+
+        collide(&mut app, ball, wall);
+        app.update();
+        assert_eq!(app.world().get::<Homing>(ball).unwrap().target, Some(enemy));
+        let velocity = app.world().get::<Velocity>(ball).unwrap().linvel;
+        assert!(velocity.abs_diff_eq(Vec2::new(0., 100.), 1e-3));
+        let bounce = app.world().get::<Bounce>(ball).unwrap();
+        assert_eq!(bounce.velocity, velocity);
+    }
+
+    #[test]
+    fn homing_turns_at_most_its_turn_rate() {
+        let mut app = projectile_app();
+        let missile = projectile(&mut app, Projectile::default());
+        let target = actor(&mut app, Vec2::new(0., 100.), Faction::FactionID(1));
+        let homing = Homing::new(1., 0.).target(Some(target));
+        app.world_mut().entity_mut(missile).insert(homing);
+        app.update();
+
+        let velocity = app.world().get::<Velocity>(missile).unwrap().linvel;
+        assert!((velocity.to_angle() - FRAME.as_secs_f32()).abs() < 1e-4);
+        assert!((velocity.length() - 100.).abs() < 1e-3);
+        let rotation = app.world().get::<Transform2d>(missile).unwrap().rotation;
+        assert!((rotation - velocity.to_angle()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn factionless_homing_acquires_hostile_actors() {
+        let mut app = projectile_app();
+        let missile = projectile(&mut app, Projectile::default());
+        app.world_mut()
+            .entity_mut(missile)
+            .insert(Homing::new(1., 200.));
+        actor(&mut app, Vec2::new(50., 0.), Faction::FriendlyToAll);
+        let enemy = actor(&mut app, Vec2::new(100., 0.), Faction::FactionID(1));
+        app.update();
+        let homing = app.world().get::<Homing>(missile).unwrap();
+        assert_eq!(homing.target, Some(enemy));
+    }
+
+    #[test]
+    fn projectiles_leave_their_shooter_without_touching_it() {
+        let mut app = physics_app();
+        app.add_plugins(ProjectilePlugin::<DummyStates>::default());
+        let body = |x: f32, rigidbody: RigidBody| {
+            let translation = Vec2::new(x, 0.);
+            (
+                Transform2d::from_translation(translation),
+                Transform::from_translation(translation.extend(0.)),
+                GlobalTransform::default(),
+                rigidbody,
+                Collider::ball(20.),
+                Velocity::zero(),
+                Health(100.),
+                Faction::FactionID(1),
+            )
+        };
+        let shooter = app.world_mut().spawn(body(0., RigidBody::Dynamic)).id();
+        let stranger = app.world_mut().spawn(body(80., RigidBody::Fixed)).id();
+        app.world_mut()
+            .entity_mut(stranger)
+            .insert(Faction::FactionID(2));
+        let bullet = ProjectileBundle {
+            velocity: Velocity::linear(Vec2::new(200., 0.)),
+            ..ProjectileBundle::default()
+        };
+        app.world_mut().spawn(bullet).insert((
+            Shooter(shooter),
+            Faction::FactionID(1),
+            Damage(10.),
+        ));
+
+        let mut collisions = ManualEventReader::<CollisionEvent>::default();
+        let mut touched_shooter = false;
+        for _ in 0..32 {
+            app.update();
+            let events = app.world().resource::<Events<CollisionEvent>>();
+            touched_shooter |= collisions.read(events).any(|event| match event {
+                CollisionEvent::Started(e1, e2, _) => *e1 == shooter || *e2 == shooter,
+                CollisionEvent::Stopped(..) => false,
+            });
+        }
+
+        assert!(!touched_shooter);
+        assert_eq!(
+            app.world().get::<Velocity>(shooter).unwrap().linvel,
+            Vec2::ZERO
+        );
+        assert_eq!(app.world().get::<Health>(shooter).unwrap().0, 100.);
+        assert_eq!(app.world().get::<Health>(stranger).unwrap().0, 90.);
+    }
+}
@@ -1,4 +1,7 @@
-use bevy::{prelude::Component, reflect::Reflect};
+use bevy::{
+    prelude::{Component, Entity, Event, Vec2},
+    reflect::Reflect,
+};
 
 #[derive(Component, Clone, Copy, PartialEq, Reflect, Debug)]
 pub struct Speed(pub f32);
@@ -8,3 +11,17 @@ pub struct Health(pub f32);
 
 #[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
 pub struct Knockback(pub f32);
+
+/// Health taken from whatever this hits: a projectile, or the weapon of a hitscan or melee attack.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
+pub struct Damage(pub f32);
+
+/// Sent every time something loses `Health` to a damage source.
+#[derive(Event, Reflect, Clone, Copy, PartialEq, Debug)]
+pub struct DamageDealt {
+    /// The projectile, or the weapon for hitscan, beam and melee hits.
+    pub source: Entity,
+    pub target: Entity,
+    pub amount: f32,
+    pub position: Vec2,
+}
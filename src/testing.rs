@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use bevy::{
+    asset::{AssetApp, AssetPlugin},
+    ecs::event::ManualEventReader,
+    hierarchy::HierarchyPlugin,
+    prelude::{
+        App, AppExtStates, BuildWorldChildren, Entity, Event, Events, GlobalTransform, Mesh,
+        ResMut, Startup, Transform, TransformPlugin, Vec2, World,
+    },
+    scene::ScenePlugin,
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+    MinimalPlugins,
+};
+use bevy_mod_transform2d::{transform2d::Transform2d, Transform2dPlugin};
+use bevy_rapier2d::prelude::{
+    Collider, CollisionEvent, RapierConfiguration, RapierContext, RapierPhysicsPlugin, RigidBody,
+};
+use bevy_turborand::prelude::RngPlugin;
+
+use crate::{
+    meta_states::DummyStates,
+    projectile::{KnockbackEvent, ProjectilePhysicsHooks},
+    weapons::{Weapon, WeaponArguments, WeaponFireMode, WeaponPlugin},
+};
+
+/// Length of a frame in `physics_app`.
+pub(crate) const PHYSICS_FRAME: Duration = Duration::from_micros(15_625);
+/// Length of a frame in `weapon_app`.
+pub(crate) const WEAPON_FRAME: Duration = Duration::from_micros(31_250);
+
+/// A headless app running Rapier without gravity, set up like `TwinStickPlugin` does it, with
+/// every update advancing time by `PHYSICS_FRAME`. Add the plugins under test on top.
+pub(crate) fn physics_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+        Transform2dPlugin,
+        RapierPhysicsPlugin::<ProjectilePhysicsHooks>::pixels_per_meter(100.),
+    ))
+    .init_asset::<Mesh>()
+    .init_state::<DummyStates>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(PHYSICS_FRAME))
+    .add_systems(Startup, |mut config: ResMut<RapierConfiguration>| {
+        config.gravity = Vec2::ZERO;
+    });
+    app
+}
+
+/// `physics_app` with `WeaponPlugin` and a seeded rng. Colliders are only in Rapier's queries after
+/// the first update.
+pub(crate) fn weapon_physics_app() -> App {
+    let mut app = physics_app();
+    app.add_plugins((
+        RngPlugin::new().with_rng_seed(7),
+        WeaponPlugin::<DummyStates>::default(),
+    ))
+    .add_event::<KnockbackEvent>();
+    app
+}
+
+/// Spawns a fixed ball collider, like a wall or a rooted actor.
+pub(crate) fn solid(world: &mut World, translation: Vec2, radius: f32) -> Entity {
+    world
+        .spawn((
+            Transform2d::from_translation(translation),
+            Transform::from_translation(translation.extend(0.)),
+            GlobalTransform::default(),
+            RigidBody::Fixed,
+            Collider::ball(radius),
+        ))
+        .id()
+}
+
+/// A headless app running `WeaponPlugin` without physics, with a seeded rng and every update
+/// advancing time by `WEAPON_FRAME`. Its first, zero-length update has already run.
+pub(crate) fn weapon_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        AssetPlugin::default(),
+        RngPlugin::new().with_rng_seed(7),
+        WeaponPlugin::<DummyStates>::default(),
+    ))
+    .init_state::<DummyStates>()
+    .init_resource::<RapierContext>()
+    .add_event::<CollisionEvent>()
+    .add_event::<KnockbackEvent>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(WEAPON_FRAME));
+    app.update();
+    app
+}
+
+/// Spawns a wielder at the origin holding a weapon with `fire_func`, returning the weapon.
+pub(crate) fn wielded(
+    world: &mut World,
+    fire_func: Box<dyn Fn(&mut WeaponArguments) + Send + Sync>,
+    fire_mode: WeaponFireMode,
+) -> Entity {
+    let weapon = world
+        .spawn(Weapon {
+            can_fire: true,
+            fire_func,
+            fire_mode,
+        })
+        .id();
+    world.spawn(Transform2d::default()).push_children(&[weapon]);
+    weapon
+}
+
+/// Events of type `E` sent since `reader` last looked.
+pub(crate) fn sent<E: Event + Copy>(app: &App, reader: &mut ManualEventReader<E>) -> Vec<E> {
+    reader
+        .read(app.world().resource::<Events<E>>())
+        .copied()
+        .collect()
+}
@@ -0,0 +1,115 @@
+use bevy::{
+    prelude::{Component, Entity, Query, With},
+    reflect::Reflect,
+};
+use bevy_mod_transform2d::transform2d::Transform2d;
+
+use crate::{
+    actors::{Actor, Faction},
+    player::CursorTracker,
+};
+
+/// Locks a cursor-aimed wielder onto the hostile actor that best matches where the cursor is
+/// pointing, within `max_angle` radians either side of the cursor direction and `max_distance`
+/// pixels. The player's shots carry the lock as their `FireWeaponEvent::target`.
+///
+/// Hostility is judged by `Faction::is_hostile_to`, with wielders without a `Faction` counting as
+/// `FriendlyToAll`. Either way they lock onto actors with a `FactionID` or `HostileToAll`.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Component)]
+pub struct AimAssist {
+    pub max_angle: f32,
+    pub max_distance: f32,
+    /// How far shots are bent from the cursor toward the locked target, from `0` for not at all
+    /// to `1` for straight at it.
+    pub strength: f32,
+    pub target: Option<Entity>,
+}
+
+impl AimAssist {
+    pub fn new(max_angle: f32, max_distance: f32) -> Self {
+        Self {
+            max_angle,
+            max_distance,
+            strength: 0.5,
+            target: None,
+        }
+    }
+
+    pub fn strength(self, strength: f32) -> Self {
+        Self { strength, ..self }
+    }
+}
+
+/// Picks the target with the lowest sum of its angle off the cursor and its distance, each
+/// relative to the cone's limits.
+pub(crate) fn acquire_aim_assist_targets(
+    mut wielders: Query<(Entity, &mut AimAssist, &Transform2d, Option<&Faction>)>,
+    candidates: Query<(Entity, &Transform2d, &Faction), With<Actor>>,
+    cursor: Query<&Transform2d, With<CursorTracker>>,
+) {
+    let Ok(cursor) = cursor.get_single() else {
+        return;
+    };
+    for (wielder, mut assist, transform, faction) in wielders.iter_mut() {
+        let origin = transform.translation;
+        let faction = faction.copied().unwrap_or(Faction::FriendlyToAll);
+        let Some(aim) = (cursor.translation - origin).try_normalize() else {
+            assist.target = None;
+            continue;
+        };
+        assist.target = candidates
+            .iter()
+            .filter(|(e, _, other)| *e != wielder && faction.is_hostile_to(other))
+            .filter_map(|(e, t, _)| {
+                let offset = t.translation - origin;
+                let distance = offset.length();
+                let angle = aim.angle_between(offset).abs();
+                (distance <= assist.max_distance && angle <= assist.max_angle)
+                    .then(|| (e, angle / assist.max_angle + distance / assist.max_distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(e, _)| e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{App, Update, Vec2},
+        MinimalPlugins,
+    };
+
+    use super::*;
+
+    fn actor(app: &mut App, translation: Vec2, faction: Option<Faction>) -> Entity {
+        let mut entity = app
+            .world_mut()
+            .spawn((Actor::default(), Transform2d::from_translation(translation)));
+        if let Some(faction) = faction {
+            entity.insert(faction);
+        }
+        entity.id()
+    }
+
+    #[test]
+    fn factionless_wielders_lock_onto_hostiles_near_the_cursor() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_systems(Update, acquire_aim_assist_targets);
+        app.world_mut().spawn((
+            CursorTracker,
+            Transform2d::from_translation(Vec2::new(100., 0.)),
+        ));
+        let wielder = actor(&mut app, Vec2::ZERO, None);
+        app.world_mut()
+            .entity_mut(wielder)
+            .insert(AimAssist::new(0.5, 200.));
+        actor(&mut app, Vec2::new(50., 5.), Some(Faction::FriendlyToAll));
+        let enemy = actor(&mut app, Vec2::new(80., 10.), Some(Faction::FactionID(1)));
+        actor(&mut app, Vec2::new(0., 80.), Some(Faction::FactionID(1)));
+        app.update();
+
+        let assist = app.world().get::<AimAssist>(wielder).unwrap();
+        assert_eq!(assist.target, Some(enemy));
+    }
+}
@@ -0,0 +1,237 @@
+use bevy::{
+    prelude::{Component, Entity, Event, EventReader, EventWriter, Query, Res},
+    reflect::Reflect,
+    time::{Time, Timer, TimerMode},
+};
+
+/// Rounds currently loaded into a weapon. A weapon without a `Magazine` never runs dry.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Component)]
+pub struct Magazine {
+    pub capacity: u32,
+    pub loaded: u32,
+    /// Rounds available to reload from. `None` means the reserve is bottomless.
+    pub reserve: Option<u32>,
+}
+
+impl Magazine {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            capacity,
+            loaded: capacity,
+            reserve: None,
+        }
+    }
+
+    pub fn with_reserve(self, reserve: u32) -> Self {
+        Self {
+            reserve: Some(reserve),
+            ..self
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.loaded == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.loaded >= self.capacity
+    }
+
+    pub fn can_reload(&self) -> bool {
+        !self.is_full() && self.reserve != Some(0)
+    }
+
+    fn refill(&mut self) {
+        let missing = self.capacity.saturating_sub(self.loaded);
+        let taken = match self.reserve {
+            Some(reserve) => missing.min(reserve),
+            None => missing,
+        };
+        self.loaded += taken;
+        if let Some(reserve) = self.reserve.as_mut() {
+            *reserve -= taken;
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct Reload {
+    pub max: f32,
+    pub timer: Timer,
+    pub reloading: bool,
+    /// Start reloading as soon as the magazine runs dry.
+    pub auto_reload: bool,
+}
+
+impl Reload {
+    pub fn new(max: f32) -> Self {
+        Self {
+            max,
+            timer: Timer::from_seconds(max, TimerMode::Once),
+            reloading: false,
+            auto_reload: true,
+        }
+    }
+
+    pub fn auto_reload(self, auto_reload: bool) -> Self {
+        Self {
+            auto_reload,
+            ..self
+        }
+    }
+}
+
+/// Ask a weapon to start reloading. Ignored if it is already reloading or can't be topped up.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct ReloadWeaponEvent {
+    pub weapon: Entity,
+}
+
+/// Sent when a weapon is fired with an empty magazine.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct EmptyClickEvent {
+    pub weapon: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct ReloadStartedEvent {
+    pub weapon: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct ReloadFinishedEvent {
+    pub weapon: Entity,
+}
+
+pub(crate) fn start_reloads(
+    mut events: EventReader<ReloadWeaponEvent>,
+    mut started_events: EventWriter<ReloadStartedEvent>,
+    mut weapons: Query<(&Magazine, &mut Reload)>,
+) {
+    for ReloadWeaponEvent { weapon } in events.read() {
+        if let Ok((magazine, mut reload)) = weapons.get_mut(*weapon) {
+            if reload.reloading || !magazine.can_reload() {
+                continue;
+            }
+            reload.reloading = true;
+            reload.timer = Timer::from_seconds(reload.max, TimerMode::Once);
+            started_events.send(ReloadStartedEvent { weapon: *weapon });
+        }
+    }
+}
+
+pub(crate) fn tick_reloads(
+    mut weapons: Query<(Entity, &mut Magazine, &mut Reload)>,
+    mut finished_events: EventWriter<ReloadFinishedEvent>,
+    time: Res<Time>,
+) {
+    for (weapon, mut magazine, mut reload) in weapons.iter_mut().filter(|(_, _, r)| r.reloading) {
+        reload.timer.tick(time.delta());
+        if reload.timer.finished() {
+            reload.reloading = false;
+            magazine.refill();
+            finished_events.send(ReloadFinishedEvent { weapon });
+        }
+    }
+}
+
+pub(crate) fn auto_reload_empty_weapons(
+    weapons: Query<(Entity, &Magazine, &Reload)>,
+    mut events: EventWriter<ReloadWeaponEvent>,
+) {
+    for (weapon, _, _) in weapons
+        .iter()
+        .filter(|(_, m, r)| r.auto_reload && !r.reloading && m.is_empty() && m.can_reload())
+    {
+        events.send(ReloadWeaponEvent { weapon });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{App, Events, IntoSystemConfigs, Update},
+        time::TimeUpdateStrategy,
+        MinimalPlugins,
+    };
+    use std::time::Duration;
+
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    fn magazine(capacity: u32, loaded: u32) -> Magazine {
+        Magazine {
+            loaded,
+            ..Magazine::new(capacity)
+        }
+    }
+
+    #[test]
+    fn refill_takes_what_the_reserve_has_left() {
+        let mut magazine = magazine(6, 1).with_reserve(3);
+        assert!(magazine.can_reload());
+        magazine.refill();
+        assert_eq!((magazine.loaded, magazine.reserve), (4, Some(0)));
+        assert!(!magazine.can_reload());
+    }
+
+    #[test]
+    fn bottomless_reserve_fills_the_magazine() {
+        let mut magazine = magazine(6, 0);
+        assert!(magazine.is_empty());
+        magazine.refill();
+        assert!(magazine.is_full());
+        assert!(!magazine.can_reload());
+    }
+
+    #[test]
+    fn reload_refills_once_its_timer_runs_out() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+            .add_event::<ReloadWeaponEvent>()
+            .add_event::<ReloadStartedEvent>()
+            .add_event::<ReloadFinishedEvent>()
+            .add_systems(Update, (start_reloads, tick_reloads).chain());
+        let weapon = app
+            .world_mut()
+            .spawn((magazine(6, 0).with_reserve(10), Reload::new(0.25)))
+            .id();
+        app.update();
+        app.world_mut().send_event(ReloadWeaponEvent { weapon });
+        app.update();
+        app.update();
+        assert!(app.world().get::<Reload>(weapon).unwrap().reloading);
+        assert_eq!(app.world().get::<Magazine>(weapon).unwrap().loaded, 0);
+
+        app.update();
+        let magazine = app.world().get::<Magazine>(weapon).unwrap();
+        assert_eq!((magazine.loaded, magazine.reserve), (6, Some(4)));
+        assert!(!app.world().get::<Reload>(weapon).unwrap().reloading);
+        assert_eq!(
+            app.world().resource::<Events<ReloadFinishedEvent>>().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn empty_magazines_reload_on_their_own() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<ReloadWeaponEvent>()
+            .add_event::<ReloadStartedEvent>()
+            .add_systems(Update, (auto_reload_empty_weapons, start_reloads).chain());
+        let weapon = app
+            .world_mut()
+            .spawn((magazine(6, 0), Reload::new(1.)))
+            .id();
+        let manual = app
+            .world_mut()
+            .spawn((magazine(6, 0), Reload::new(1.).auto_reload(false)))
+            .id();
+        app.update();
+        assert!(app.world().get::<Reload>(weapon).unwrap().reloading);
+        assert!(!app.world().get::<Reload>(manual).unwrap().reloading);
+    }
+}
@@ -0,0 +1,341 @@
+use bevy::{
+    prelude::{
+        Color, Commands, Component, DespawnRecursiveExt, Entity, Event, EventReader, EventWriter,
+        Name, Parent, Query, Res, Vec2, With, Without, World,
+    },
+    reflect::Reflect,
+    sprite::Sprite,
+    time::{Time, Timer, TimerMode},
+};
+use bevy_mod_transform2d::transform2d::Transform2d;
+use bevy_rapier2d::prelude::{QueryFilter, RapierContext};
+
+use crate::{
+    player::CursorTracker,
+    projectile::KnockbackEvent,
+    stats::{DamageDealt, Health},
+    transform2d_mods::Sprite2dBundle,
+};
+
+use super::{
+    modifiers::{ShotModifiers, WeaponModifiers, WeaponStat},
+    WeaponArguments,
+};
+
+/// A continuous beam from the wielder to the first obstacle in the aim direction, dealing damage
+/// in ticks to whatever it touches.
+///
+/// Beams stay on for `linger` seconds after the last time they were fired, so pair them with
+/// `WeaponFireMode::FullAuto` and a cooldown shorter than `linger`.
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct Beam {
+    pub max_range: f32,
+    pub width: f32,
+    pub color: Color,
+    pub damage_per_second: f32,
+    /// Knockback applied on every damage tick.
+    pub knockback: f32,
+    pub tick_timer: Timer,
+    pub linger_timer: Timer,
+    pub firing: bool,
+    pub target: Option<Entity>,
+    /// Offset of the muzzle the beam comes out of, like a `Muzzles` offset. Set from the shot
+    /// that turned the beam on.
+    pub muzzle: Vec2,
+    pub segment: Option<Entity>,
+}
+
+impl Beam {
+    pub fn new(max_range: f32, damage_per_second: f32) -> Self {
+        Self {
+            max_range,
+            width: 4.,
+            color: Color::WHITE,
+            damage_per_second,
+            knockback: 0.,
+            tick_timer: Timer::from_seconds(0.1, TimerMode::Repeating),
+            linger_timer: Timer::from_seconds(0.1, TimerMode::Once),
+            firing: false,
+            target: None,
+            muzzle: Vec2::ZERO,
+            segment: None,
+        }
+    }
+
+    pub fn width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn knockback(self, knockback: f32) -> Self {
+        Self { knockback, ..self }
+    }
+
+    pub fn tick_interval(self, interval: f32) -> Self {
+        Self {
+            tick_timer: Timer::from_seconds(interval, TimerMode::Repeating),
+            ..self
+        }
+    }
+
+    pub fn linger(self, linger: f32) -> Self {
+        Self {
+            linger_timer: Timer::from_seconds(linger, TimerMode::Once),
+            ..self
+        }
+    }
+
+    /// The `Weapon::fire_func` for a weapon with a `Beam`: every shot keeps the beam on. The beam
+    /// comes out of the first muzzle of the shot, if the weapon has `Muzzles`.
+    pub fn fire_func() -> Box<dyn Fn(&mut WeaponArguments) + Send + Sync> {
+        Box::new(|args: &mut WeaponArguments| {
+            let (weapon, target) = (args.weapon, args.target);
+            let muzzle = args.muzzles.first().copied().unwrap_or(Vec2::ZERO);
+            args.commands.add(move |world: &mut World| {
+                if let Some(mut beam) = world.get_mut::<Beam>(weapon) {
+                    beam.hold(target, muzzle);
+                }
+            });
+        })
+    }
+
+    fn hold(&mut self, target: Option<Entity>, muzzle: Vec2) {
+        if !self.firing {
+            self.firing = true;
+            self.tick_timer.reset();
+        }
+        self.linger_timer.reset();
+        self.target = target;
+        self.muzzle = muzzle;
+    }
+
+    /// Turns the beam off and despawns its segment.
+    pub fn stop(&mut self, commands: &mut Commands) {
+        self.firing = false;
+        if let Some(segment) = self.segment.take() {
+            commands.entity(segment).despawn_recursive();
+        }
+    }
+}
+
+/// The visible part of a beam, kept between the wielder and whatever the beam hits.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Component)]
+pub struct BeamSegment {
+    pub weapon: Entity,
+}
+
+/// Sent for every damage tick of a beam on the entity it touches.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Event)]
+pub struct BeamHitEvent {
+    pub weapon: Entity,
+    pub impacted: Entity,
+    pub point: Vec2,
+    pub direction: Vec2,
+    pub damage: f32,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_beams(
+    mut commands: Commands,
+    mut beams: Query<(Entity, &mut Beam, &Parent)>,
+    cursor: Query<Entity, With<CursorTracker>>,
+    transforms: Query<&Transform2d, Without<BeamSegment>>,
+    mut segments: Query<(&mut Transform2d, &mut Sprite), With<BeamSegment>>,
+    mut hit_events: EventWriter<BeamHitEvent>,
+    modifiers: Query<&WeaponModifiers>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    for (weapon, mut beam, parent) in beams.iter_mut().filter(|(_, b, _)| b.firing) {
+        beam.linger_timer.tick(time.delta());
+        if beam.linger_timer.finished() {
+            beam.stop(&mut commands);
+            continue;
+        }
+
+        let Ok(wielder) = transforms.get(parent.get()).map(|t| t.translation) else {
+            continue;
+        };
+        let position = |entity: Entity| transforms.get(entity).ok().map(|t| t.translation);
+        // A target that's gone stays gone, so the beam falls back to the cursor for good.
+        let target = beam.target.and_then(position);
+        if target.is_none() && beam.target.is_some() {
+            beam.target = None;
+        }
+        let Some(aim) = target.or_else(|| cursor.get_single().ok().and_then(position)) else {
+            continue;
+        };
+        let facing = (aim - wielder).try_normalize().unwrap_or(Vec2::X);
+        let origin = wielder + facing.rotate(beam.muzzle);
+        let direction = (aim - origin).try_normalize().unwrap_or(facing);
+        let hit = rapier_context.cast_ray(
+            origin,
+            direction,
+            beam.max_range,
+            true,
+            QueryFilter::new()
+                .exclude_sensors()
+                .exclude_rigid_body(parent.get()),
+        );
+        let length = hit.map_or(beam.max_range, |(_, distance)| distance);
+
+        let transform = Transform2d {
+            translation: origin + direction * length / 2.,
+            rotation: direction.to_angle(),
+            ..Default::default()
+        };
+        let size = Vec2::new(length, beam.width);
+        match beam.segment.and_then(|s| segments.get_mut(s).ok()) {
+            Some((mut segment_transform, mut sprite)) => {
+                *segment_transform = transform;
+                sprite.custom_size = Some(size);
+            }
+            None => {
+                let segment = commands
+                    .spawn((
+                        Name::new("Beam"),
+                        BeamSegment { weapon },
+                        Sprite2dBundle {
+                            sprite: Sprite {
+                                color: beam.color,
+                                custom_size: Some(size),
+                                ..Default::default()
+                            },
+                            transform,
+                            ..Default::default()
+                        },
+                    ))
+                    .id();
+                beam.segment = Some(segment);
+            }
+        }
+
+        beam.tick_timer.tick(time.delta());
+        let ticks = beam.tick_timer.times_finished_this_tick();
+        if let Some((impacted, _)) = hit {
+            let damage_per_second = ShotModifiers::of(&modifiers, weapon, parent.get())
+                .apply(WeaponStat::Damage, beam.damage_per_second);
+            let damage = damage_per_second * beam.tick_timer.duration().as_secs_f32();
+            for _ in 0..ticks {
+                hit_events.send(BeamHitEvent {
+                    weapon,
+                    impacted,
+                    point: origin + direction * length,
+                    direction,
+                    damage,
+                });
+            }
+        }
+    }
+}
+
+pub(crate) fn apply_beam_hits(
+    mut hit_events: EventReader<BeamHitEvent>,
+    mut knockback_events: EventWriter<KnockbackEvent>,
+    mut damage_events: EventWriter<DamageDealt>,
+    beams: Query<(&Beam, &Parent)>,
+    modifiers: Query<&WeaponModifiers>,
+    mut health: Query<&mut Health>,
+) {
+    for BeamHitEvent {
+        weapon,
+        impacted,
+        point,
+        direction,
+        damage,
+    } in hit_events.read()
+    {
+        if let Ok(mut health) = health.get_mut(*impacted) {
+            health.0 -= damage;
+            damage_events.send(DamageDealt {
+                source: *weapon,
+                target: *impacted,
+                amount: *damage,
+                position: *point,
+            });
+        }
+        if let Ok((beam, parent)) = beams.get(*weapon) {
+            let knockback = ShotModifiers::of(&modifiers, *weapon, parent.get())
+                .apply(WeaponStat::Knockback, beam.knockback);
+            if knockback > 0. {
+                knockback_events.send(KnockbackEvent {
+                    entity: *impacted,
+                    direction: *direction,
+                    force: knockback,
+                });
+            }
+        }
+    }
+}
+
+/// Turns every beam off, e.g. when the plugin's control state stops being active.
+pub(crate) fn clear_beams(mut commands: Commands, mut beams: Query<&mut Beam>) {
+    for mut beam in beams.iter_mut() {
+        beam.stop(&mut commands);
+    }
+}
+
+pub(crate) fn despawn_orphaned_beam_segments(
+    mut commands: Commands,
+    segments: Query<(Entity, &BeamSegment)>,
+    beams: Query<(), With<Beam>>,
+) {
+    for (segment, BeamSegment { weapon }) in segments.iter() {
+        if beams.get(*weapon).is_err() {
+            commands.entity(segment).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use bevy::prelude::App;
+
+    use super::*;
+    use crate::{
+        testing::{weapon_app, wielded},
+        weapons::{FireWeaponEvent, WeaponAction, WeaponFireMode},
+    };
+
+    fn segment_rotation(app: &mut App) -> f32 {
+        let mut segments = app
+            .world_mut()
+            .query_filtered::<&Transform2d, With<BeamSegment>>();
+        segments.single(app.world()).rotation
+    }
+
+    #[test]
+    fn beam_falls_back_to_the_cursor_once_its_target_is_gone() {
+        let mut app = weapon_app();
+        let world = app.world_mut();
+        let weapon = wielded(world, Beam::fire_func(), WeaponFireMode::FullAuto);
+        world
+            .entity_mut(weapon)
+            .insert(Beam::new(100., 10.).linger(1.));
+        world.spawn((
+            CursorTracker,
+            Transform2d::from_translation(Vec2::new(0., 50.)),
+        ));
+        let target = world
+            .spawn(Transform2d::from_translation(Vec2::new(50., 0.)))
+            .id();
+        app.world_mut().send_event(FireWeaponEvent {
+            weapon,
+            target: Some(target),
+            action: WeaponAction::Primary,
+        });
+        app.update();
+        app.update();
+        assert!(segment_rotation(&mut app).abs() < 1e-4);
+
+        app.world_mut().despawn(target);
+        app.update();
+        assert!((segment_rotation(&mut app) - FRAC_PI_2).abs() < 1e-4);
+        assert_eq!(app.world().get::<Beam>(weapon).unwrap().target, None);
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::{
+    prelude::{Component, Entity, EventWriter, Query, Res},
+    reflect::Reflect,
+    time::{Time, Timer, TimerMode},
+};
+
+use super::{FireWeaponEvent, WeaponAction};
+
+/// Follow-up shots still owed by a `WeaponFireMode::Burst` trigger pull. Inserted on the
+/// weapon the first time it fires a burst.
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct BurstState {
+    /// Shots that have not been scheduled yet.
+    pub remaining: u32,
+    /// Shots that have been sent as `FireWeaponEvent`s but not fired yet.
+    pub queued: u32,
+    pub target: Option<Entity>,
+    /// The action that started the burst.
+    pub action: WeaponAction,
+    pub timer: Timer,
+}
+
+impl BurstState {
+    pub fn new(count: u32, interval: f32, target: Option<Entity>, action: WeaponAction) -> Self {
+        Self {
+            remaining: count.saturating_sub(1),
+            queued: 0,
+            target,
+            action,
+            timer: Timer::from_seconds(interval, TimerMode::Repeating),
+        }
+    }
+
+    pub fn in_progress(&self) -> bool {
+        self.remaining > 0 || self.queued > 0
+    }
+
+    /// Whether a `FireWeaponEvent` for this weapon should be honoured. While a burst is running
+    /// only the shots it queued itself are let through.
+    pub fn accepts_shot(&self, action: WeaponAction) -> bool {
+        !self.in_progress() || (self.queued > 0 && action == self.action)
+    }
+
+    pub fn cancel(&mut self) {
+        self.remaining = 0;
+        self.queued = 0;
+    }
+}
+
+pub(crate) fn tick_bursts(
+    mut bursts: Query<(Entity, &mut BurstState)>,
+    mut events: EventWriter<FireWeaponEvent>,
+    time: Res<Time>,
+) {
+    for (weapon, mut burst) in bursts.iter_mut().filter(|(_, b)| b.remaining > 0) {
+        burst.timer.tick(time.delta());
+        let due = burst.timer.times_finished_this_tick().min(burst.remaining);
+        burst.remaining -= due;
+        burst.queued += due;
+        for _ in 0..due {
+            events.send(FireWeaponEvent {
+                weapon,
+                target: burst.target,
+                action: burst.action,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{App, Update},
+        time::TimeUpdateStrategy,
+        MinimalPlugins,
+    };
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn running_burst_only_takes_the_shots_it_queued() {
+        let mut burst = BurstState::new(3, 0.1, None, WeaponAction::Primary);
+        assert_eq!(burst.remaining, 2);
+        assert!(!burst.accepts_shot(WeaponAction::Primary));
+        burst.queued = 1;
+        assert!(burst.accepts_shot(WeaponAction::Primary));
+        assert!(!burst.accepts_shot(WeaponAction::Secondary));
+        burst.cancel();
+        assert!(!burst.in_progress());
+        assert!(burst.accepts_shot(WeaponAction::Secondary));
+    }
+
+    #[test]
+    fn follow_up_shots_are_queued_an_interval_apart() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                60,
+            )))
+            .add_event::<FireWeaponEvent>()
+            .add_systems(Update, tick_bursts);
+        let weapon = app
+            .world_mut()
+            .spawn(BurstState::new(3, 0.1, None, WeaponAction::Primary))
+            .id();
+        let mut queued = Vec::new();
+        for _ in 0..5 {
+            app.update();
+            let burst = app.world().get::<BurstState>(weapon).unwrap();
+            queued.push((burst.remaining, burst.queued));
+        }
+        assert_eq!(queued, [(2, 0), (2, 0), (1, 1), (1, 1), (0, 2)]);
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::{
+    prelude::{Commands, Component, Entity, Event, EventReader, EventWriter, Query, Res},
+    reflect::Reflect,
+    time::Time,
+};
+
+use super::{FireWeaponEvent, SecondaryFire, Weapon, WeaponAction, WeaponFireMode};
+
+/// How long a `WeaponFireMode::Charge` weapon has been held. Inserted on the weapon the first
+/// time it starts charging.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Default, Component)]
+pub struct ChargeState {
+    pub elapsed: f32,
+    pub charging: bool,
+    pub target: Option<Entity>,
+    /// The action being charged.
+    pub action: WeaponAction,
+}
+
+impl ChargeState {
+    /// Charge normalized against the time it takes to fully charge, in `0..=1`.
+    pub fn level(&self, max_charge: f32) -> f32 {
+        if max_charge <= 0. {
+            1.
+        } else {
+            (self.elapsed / max_charge).clamp(0., 1.)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct StartChargeEvent {
+    pub weapon: Entity,
+    pub target: Option<Entity>,
+    pub action: WeaponAction,
+}
+
+/// Let go of a charging weapon. It fires if it reached its minimum charge and fizzles otherwise.
+/// Ignored unless `action` is the one being charged.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct ReleaseChargeEvent {
+    pub weapon: Entity,
+    pub action: WeaponAction,
+}
+
+/// Sent every frame a weapon is charging, and once with a level of `0` when it stops.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Event)]
+pub struct ChargeProgressEvent {
+    pub weapon: Entity,
+    pub level: f32,
+}
+
+pub(crate) fn start_charges(
+    mut events: EventReader<StartChargeEvent>,
+    mut commands: Commands,
+    mut weapons: Query<(&Weapon, Option<&SecondaryFire>, Option<&mut ChargeState>)>,
+) {
+    for StartChargeEvent {
+        weapon,
+        target,
+        action,
+    } in events.read()
+    {
+        if let Ok((weapon_component, secondary, state)) = weapons.get_mut(*weapon) {
+            if !matches!(
+                weapon_component.action(secondary, *action),
+                Some((WeaponFireMode::Charge { .. }, true))
+            ) || state.as_ref().is_some_and(|s| s.charging)
+            {
+                continue;
+            }
+            let charging = ChargeState {
+                elapsed: 0.,
+                charging: true,
+                target: *target,
+                action: *action,
+            };
+            match state {
+                Some(mut state) => *state = charging,
+                None => {
+                    commands.entity(*weapon).insert(charging);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn tick_charges(
+    mut weapons: Query<(Entity, &Weapon, Option<&SecondaryFire>, &mut ChargeState)>,
+    mut fire_events: EventWriter<FireWeaponEvent>,
+    mut progress_events: EventWriter<ChargeProgressEvent>,
+    time: Res<Time>,
+) {
+    for (weapon, weapon_component, secondary, mut state) in weapons.iter_mut() {
+        if !state.charging {
+            continue;
+        }
+        let Some((
+            WeaponFireMode::Charge {
+                max_charge,
+                auto_release,
+                ..
+            },
+            _,
+        )) = weapon_component.action(secondary, state.action)
+        else {
+            state.charging = false;
+            continue;
+        };
+        state.elapsed = (state.elapsed + time.delta_seconds()).min(max_charge);
+        let level = state.level(max_charge);
+        if auto_release && level >= 1. {
+            state.charging = false;
+            fire_events.send(FireWeaponEvent {
+                weapon,
+                target: state.target,
+                action: state.action,
+            });
+            progress_events.send(ChargeProgressEvent { weapon, level: 0. });
+        } else {
+            progress_events.send(ChargeProgressEvent { weapon, level });
+        }
+    }
+}
+
+pub(crate) fn release_charges(
+    mut events: EventReader<ReleaseChargeEvent>,
+    mut weapons: Query<(&Weapon, Option<&SecondaryFire>, &mut ChargeState)>,
+    mut fire_events: EventWriter<FireWeaponEvent>,
+    mut progress_events: EventWriter<ChargeProgressEvent>,
+) {
+    for ReleaseChargeEvent { weapon, action } in events.read() {
+        if let Ok((weapon_component, secondary, mut state)) = weapons.get_mut(*weapon) {
+            if !state.charging || state.action != *action {
+                continue;
+            }
+            state.charging = false;
+            if let Some((
+                WeaponFireMode::Charge {
+                    max_charge,
+                    min_charge,
+                    ..
+                },
+                _,
+            )) = weapon_component.action(secondary, *action)
+            {
+                if state.level(max_charge) >= min_charge {
+                    fire_events.send(FireWeaponEvent {
+                        weapon: *weapon,
+                        target: state.target,
+                        action: *action,
+                    });
+                } else {
+                    state.elapsed = 0.;
+                }
+            }
+            progress_events.send(ChargeProgressEvent {
+                weapon: *weapon,
+                level: 0.,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::event::ManualEventReader,
+        prelude::{App, Events, IntoSystemConfigs, Update},
+        time::TimeUpdateStrategy,
+        MinimalPlugins,
+    };
+    use std::time::Duration;
+
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    fn app(min_charge: f32, auto_release: bool) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+            .add_event::<StartChargeEvent>()
+            .add_event::<ReleaseChargeEvent>()
+            .add_event::<ChargeProgressEvent>()
+            .add_event::<FireWeaponEvent>()
+            .add_systems(
+                Update,
+                (start_charges, tick_charges, release_charges).chain(),
+            );
+        let weapon = app
+            .world_mut()
+            .spawn(Weapon {
+                can_fire: true,
+                fire_func: Box::new(|_| ()),
+                fire_mode: WeaponFireMode::Charge {
+                    max_charge: 0.4,
+                    min_charge,
+                    auto_release,
+                },
+            })
+            .id();
+        app.update();
+        app.world_mut().send_event(StartChargeEvent {
+            weapon,
+            target: None,
+            action: WeaponAction::Primary,
+        });
+        (app, weapon)
+    }
+
+    fn fired(app: &App, reader: &mut ManualEventReader<FireWeaponEvent>) -> usize {
+        reader
+            .read(app.world().resource::<Events<FireWeaponEvent>>())
+            .count()
+    }
+
+    fn release(app: &mut App, weapon: Entity) {
+        app.world_mut().send_event(ReleaseChargeEvent {
+            weapon,
+            action: WeaponAction::Primary,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn level_is_clamped_to_full_charge() {
+        let charge = ChargeState {
+            elapsed: 3.,
+            ..Default::default()
+        };
+        assert_eq!(charge.level(2.), 1.);
+        assert_eq!(charge.level(6.), 0.5);
+        assert_eq!(charge.level(0.), 1.);
+    }
+
+    #[test]
+    fn early_release_fizzles() {
+        let (mut app, weapon) = app(0.75, false);
+        let mut reader = ManualEventReader::default();
+        app.update();
+        release(&mut app, weapon);
+        assert_eq!(fired(&app, &mut reader), 0);
+        let charge = app.world().get::<ChargeState>(weapon).unwrap();
+        assert!(!charge.charging);
+        assert_eq!(charge.elapsed, 0.);
+    }
+
+    #[test]
+    fn release_past_the_minimum_fires() {
+        let (mut app, weapon) = app(0.5, false);
+        let mut reader = ManualEventReader::default();
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(fired(&app, &mut reader), 0);
+        release(&mut app, weapon);
+        assert_eq!(fired(&app, &mut reader), 1);
+    }
+
+    #[test]
+    fn full_charge_releases_on_its_own() {
+        let (mut app, weapon) = app(0.5, true);
+        let mut reader = ManualEventReader::default();
+        for _ in 0..5 {
+            app.update();
+        }
+        assert_eq!(fired(&app, &mut reader), 1);
+        assert!(!app.world().get::<ChargeState>(weapon).unwrap().charging);
+    }
+}
@@ -0,0 +1,285 @@
+use bevy::{
+    asset::{
+        io::Reader, ron, Asset, AssetEvent, AssetId, AssetLoader, Assets, AsyncReadExt, Handle,
+        LoadContext,
+    },
+    prelude::{Commands, Entity, EventReader, Query, Res},
+    reflect::Reflect,
+    utils::HashSet,
+};
+use bevy_rapier2d::prelude::{Collider, ColliderMassProperties};
+use serde::Deserialize;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::{
+    projectile::{
+        Bounce, Homing, Knockback, Lifespan, Pierce, Projectile, ProjectileBundle,
+        ProjectileImpactBehavior,
+    },
+    stats::Damage,
+};
+
+use super::{
+    patterns::{fire_pattern, FirePattern, Spread},
+    Cooldown, Weapon, WeaponArguments, WeaponFireMode,
+};
+
+/// A weapon described in data rather than code, loaded from `.weapon.ron` files.
+///
+/// Put a `Handle<WeaponDefinition>` on an entity parented to an actor and it gets a `Weapon` and
+/// `Cooldown` built from the definition. The weapon is rebuilt whenever the file changes, so with
+/// bevy's `file_watcher` feature enabled definitions hot reload.
+#[derive(Asset, Reflect, Clone, PartialEq, Debug, Deserialize)]
+pub struct WeaponDefinition {
+    pub cooldown: f32,
+    #[serde(default = "default_fire_mode")]
+    pub fire_mode: WeaponFireMode,
+    /// Projectile speed in pixels per second.
+    pub speed: f32,
+    /// Total angle in radians that the projectiles of one shot are fanned out over.
+    #[serde(default)]
+    pub spread: f32,
+    /// Projectiles spawned per shot.
+    #[serde(default = "default_count")]
+    pub count: u32,
+    #[serde(default)]
+    pub knockback: Option<f32>,
+    /// Health every projectile takes from whatever it hits. Like `knockback`, it is put on the
+    /// weapon so damage upgrades apply to it.
+    #[serde(default)]
+    pub damage: Option<f32>,
+    #[serde(default)]
+    pub projectile: ProjectileDefinition,
+}
+
+fn default_fire_mode() -> WeaponFireMode {
+    WeaponFireMode::SemiAuto
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Reflect, Clone, Copy, PartialEq, Debug, Deserialize)]
+#[serde(default)]
+pub struct ProjectileDefinition {
+    pub radius: f32,
+    pub density: f32,
+    /// Seconds before the projectile despawns on its own.
+    pub lifespan: f32,
+    pub on_hit: ProjectileImpactBehavior,
+    pub on_impact: ProjectileImpactBehavior,
+    /// Extra actors a projectile with `on_hit: Pierce` passes through, without limit if unset.
+    pub pierce: Option<u32>,
+    /// Fraction of its damage a piercing projectile loses on every pierce.
+    pub pierce_falloff: f32,
+    /// Times a bouncing projectile can bounce before it dies, without limit if unset.
+    pub bounces: Option<u32>,
+    /// How far a bouncing projectile looks for an enemy to turn toward after every bounce.
+    pub bounce_retarget: Option<f32>,
+    /// Radians per second a homing projectile turns toward its target. Projectiles only home
+    /// when this is set.
+    pub homing_turn_rate: Option<f32>,
+    /// How far a homing projectile looks for a new target when it has none.
+    pub homing_radius: f32,
+}
+
+impl Default for ProjectileDefinition {
+    fn default() -> Self {
+        Self {
+            radius: 5.,
+            density: 1.,
+            lifespan: 0.4,
+            on_hit: ProjectileImpactBehavior::Die,
+            on_impact: ProjectileImpactBehavior::Die,
+            pierce: None,
+            pierce_falloff: 0.,
+            bounces: None,
+            bounce_retarget: None,
+            homing_turn_rate: None,
+            homing_radius: 300.,
+        }
+    }
+}
+
+impl WeaponDefinition {
+    pub fn cooldown(&self) -> Cooldown {
+        Cooldown::new(self.cooldown)
+    }
+
+    pub fn weapon(&self) -> Weapon {
+        let definition = self.clone();
+        Weapon {
+            can_fire: true,
+            fire_mode: self.fire_mode,
+            fire_func: Box::new(move |args: &mut WeaponArguments| definition.fire(args)),
+        }
+    }
+
+    pub fn pattern(&self) -> FirePattern {
+        FirePattern::Shotgun {
+            count: self.count,
+            angle: self.spread,
+            spread: Spread::Even,
+        }
+    }
+
+    /// The projectile every shot spawns, before it is positioned and given its velocity.
+    pub fn projectile_bundle(&self) -> ProjectileBundle {
+        ProjectileBundle {
+            projectile: Projectile {
+                on_hit: self.projectile.on_hit,
+                on_impact: self.projectile.on_impact,
+            },
+            mass_properties: ColliderMassProperties::Density(self.projectile.density),
+            collider: Collider::ball(self.projectile.radius),
+            ..Default::default()
+        }
+    }
+
+    fn fire(&self, args: &mut WeaponArguments) {
+        let template = self.projectile_bundle();
+        for projectile in fire_pattern(args, &self.pattern(), 0, self.speed, &template) {
+            let mut projectile = args.commands.entity(projectile);
+            projectile.insert(Lifespan::new(Duration::from_secs_f32(
+                self.projectile.lifespan,
+            )));
+            if self.projectile.on_hit == ProjectileImpactBehavior::Pierce {
+                let remaining = self.projectile.pierce.unwrap_or(u32::MAX);
+                projectile.insert(Pierce::new(remaining).falloff(self.projectile.pierce_falloff));
+            }
+            if let Some(remaining) = self.projectile.bounces {
+                let bounce = Bounce::new(remaining);
+                projectile.insert(match self.projectile.bounce_retarget {
+                    Some(radius) => bounce.retarget(radius),
+                    None => bounce,
+                });
+            }
+            if let Some(turn_rate) = self.projectile.homing_turn_rate {
+                projectile.insert(
+                    Homing::new(turn_rate, self.projectile.homing_radius).target(args.target),
+                );
+            }
+            args.modifiers.modify_projectile(&mut projectile);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct WeaponDefinitionLoader;
+
+#[derive(Debug, Error)]
+pub enum WeaponDefinitionLoaderError {
+    #[error("could not read weapon definition: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse weapon definition: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for WeaponDefinitionLoader {
+    type Asset = WeaponDefinition;
+    type Settings = ();
+    type Error = WeaponDefinitionLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["weapon.ron"]
+    }
+}
+
+/// Builds weapons for newly spawned definition handles and rebuilds them when their definition
+/// is (re)loaded.
+pub(crate) fn apply_weapon_definitions(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<WeaponDefinition>>,
+    definitions: Res<Assets<WeaponDefinition>>,
+    weapons: Query<(Entity, &Handle<WeaponDefinition>, Option<&Weapon>)>,
+) {
+    let changed: HashSet<AssetId<WeaponDefinition>> = asset_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    for (entity, handle, weapon) in weapons.iter() {
+        if weapon.is_some() && !changed.contains(&handle.id()) {
+            continue;
+        }
+        if let Some(definition) = definitions.get(handle) {
+            let mut weapon = commands.entity(entity);
+            weapon.insert((definition.weapon(), definition.cooldown()));
+            match definition.knockback {
+                Some(knockback) => weapon.insert(Knockback(knockback)),
+                None => weapon.remove::<Knockback>(),
+            };
+            match definition.damage {
+                Some(damage) => weapon.insert(Damage(damage)),
+                None => weapon.remove::<Damage>(),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shotgun_definition_parses() {
+        let shotgun: WeaponDefinition =
+            ron::de::from_str(include_str!("../../assets/weapons/shotgun.weapon.ron")).unwrap();
+        assert_eq!(shotgun.cooldown, 0.6);
+        assert_eq!(shotgun.fire_mode, WeaponFireMode::SemiAuto);
+        assert_eq!(
+            (shotgun.speed, shotgun.spread, shotgun.count),
+            (900., 0.4, 5)
+        );
+        assert_eq!(shotgun.knockback, Some(40.));
+        assert_eq!(shotgun.damage, Some(12.));
+        assert_eq!(shotgun.projectile.radius, 3.);
+        assert_eq!(shotgun.projectile.lifespan, 0.35);
+        assert_eq!(shotgun.projectile.on_hit, ProjectileImpactBehavior::Die);
+    }
+
+    #[test]
+    fn missing_fields_use_their_defaults() {
+        let definition: WeaponDefinition =
+            ron::de::from_str("(cooldown: 0.1, speed: 500.)").unwrap();
+        assert_eq!(definition.fire_mode, WeaponFireMode::SemiAuto);
+        assert_eq!((definition.spread, definition.count), (0., 1));
+        assert_eq!(definition.knockback, None);
+        assert_eq!(definition.projectile, ProjectileDefinition::default());
+    }
+
+    #[test]
+    fn fire_modes_with_fields_parse() {
+        let definition: WeaponDefinition = ron::de::from_str(
+            "(cooldown: 0.1, speed: 500., fire_mode: Burst(count: 3, interval: 0.05))",
+        )
+        .unwrap();
+        assert_eq!(
+            definition.fire_mode,
+            WeaponFireMode::Burst {
+                count: 3,
+                interval: 0.05
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_definitions_are_rejected() {
+        assert!(ron::de::from_str::<WeaponDefinition>("(speed: 500.)").is_err());
+    }
+}
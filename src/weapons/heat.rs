@@ -0,0 +1,157 @@
+use bevy::{
+    prelude::{Component, Entity, Event, EventWriter, Query, Res},
+    reflect::Reflect,
+    time::Time,
+};
+
+use super::{Cooldown, SecondaryFire, Weapon};
+
+/// Heat that builds up with every shot and bleeds off over time. A weapon that reaches `max`
+/// overheats and can't fire until it has cooled down to `recover_at`. Works with or without a
+/// `Cooldown`.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Component)]
+pub struct Heat {
+    pub heat: f32,
+    pub max: f32,
+    pub per_shot: f32,
+    /// Heat lost per second.
+    pub dissipation: f32,
+    /// Heat the weapon has to drop to before an overheat ends. Set it to `max` to only block
+    /// firing while the weapon is actually at its limit.
+    pub recover_at: f32,
+    pub overheated: bool,
+}
+
+impl Heat {
+    pub fn new(max: f32, per_shot: f32, dissipation: f32) -> Self {
+        Self {
+            heat: 0.,
+            max,
+            per_shot,
+            dissipation,
+            recover_at: 0.,
+            overheated: false,
+        }
+    }
+
+    pub fn recover_at(self, recover_at: f32) -> Self {
+        Self { recover_at, ..self }
+    }
+
+    /// Current heat as a fraction of `max`.
+    pub fn level(&self) -> f32 {
+        if self.max > 0. {
+            (self.heat / self.max).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct OverheatStartedEvent {
+    pub weapon: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct OverheatEndedEvent {
+    pub weapon: Entity,
+}
+
+pub(crate) fn update_heat(
+    mut weapons: Query<(
+        Entity,
+        &mut Weapon,
+        Option<&mut SecondaryFire>,
+        Option<&Cooldown>,
+        &mut Heat,
+    )>,
+    mut started_events: EventWriter<OverheatStartedEvent>,
+    mut ended_events: EventWriter<OverheatEndedEvent>,
+    time: Res<Time>,
+) {
+    for (entity, mut weapon, mut secondary, cooldown, mut heat) in weapons.iter_mut() {
+        if !heat.overheated && heat.heat >= heat.max {
+            heat.overheated = true;
+            weapon.can_fire = false;
+            if let Some(secondary) = secondary.as_mut() {
+                secondary.can_fire = false;
+            }
+            started_events.send(OverheatStartedEvent { weapon: entity });
+        }
+        heat.heat = (heat.heat - heat.dissipation * time.delta_seconds()).max(0.);
+        if heat.overheated && heat.heat <= heat.recover_at {
+            heat.overheated = false;
+            // Weapons still on cooldown are left for the cooldown systems to re-enable.
+            let cooling_down = cooldown.is_some_and(|c| !c.timer.finished());
+            if !cooling_down {
+                weapon.can_fire = true;
+            }
+            if let Some(secondary) = secondary.as_mut() {
+                if secondary.cooldown.timer.finished() {
+                    secondary.can_fire = true;
+                }
+            }
+            ended_events.send(OverheatEndedEvent { weapon: entity });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        ecs::event::ManualEventReader,
+        prelude::{App, Update},
+        time::TimeUpdateStrategy,
+        MinimalPlugins,
+    };
+
+    use super::*;
+    use crate::{testing::sent, weapons::WeaponFireMode};
+
+    #[test]
+    fn overheat_ends_without_a_cooldown() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<OverheatStartedEvent>()
+            .add_event::<OverheatEndedEvent>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )))
+            .add_systems(Update, update_heat);
+        let heat = Heat {
+            heat: 1.,
+            ..Heat::new(1., 0.25, 2.).recover_at(0.25)
+        };
+        let weapon = app
+            .world_mut()
+            .spawn((
+                Weapon {
+                    can_fire: true,
+                    fire_func: Box::new(|_| ()),
+                    fire_mode: WeaponFireMode::FullAuto,
+                },
+                heat,
+            ))
+            .id();
+        let mut started = ManualEventReader::<OverheatStartedEvent>::default();
+        let mut ended = ManualEventReader::<OverheatEndedEvent>::default();
+
+        // The first update has no delta time, so the weapon only overheats.
+        app.update();
+        assert_eq!(sent(&app, &mut started).len(), 1);
+        assert!(!app.world().get::<Weapon>(weapon).unwrap().can_fire);
+
+        app.update();
+        let heat = app.world().get::<Heat>(weapon).unwrap();
+        assert_eq!(heat.heat, 0.5);
+        assert!(heat.overheated);
+
+        app.update();
+        assert_eq!(sent(&app, &mut ended).len(), 1);
+        assert!(!app.world().get::<Heat>(weapon).unwrap().overheated);
+        assert!(app.world().get::<Weapon>(weapon).unwrap().can_fire);
+    }
+}
@@ -0,0 +1,256 @@
+use bevy::{
+    prelude::{Entity, Event, EventReader, EventWriter, Query, Vec2, World},
+    reflect::Reflect,
+};
+use bevy_rapier2d::prelude::{QueryFilter, RapierContext};
+
+use crate::{
+    actors::Hittable,
+    projectile::{Knockback, KnockbackEvent},
+    stats::{Damage, DamageDealt, Health},
+};
+
+use super::{
+    modifiers::{ShotModifiers, WeaponModifiers, WeaponStat},
+    patterns::{aim, bloom, muzzle_origins},
+    WeaponArguments,
+};
+
+/// An instant ray from the wielder, or from each muzzle the shot comes out of, toward the aim
+/// point. The ray passes through up to `penetration` actors, i.e. anything with `Actor` or
+/// `Health`, and stops at the first collider that isn't one.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug)]
+pub struct Hitscan {
+    pub max_range: f32,
+    pub penetration: u32,
+}
+
+impl Hitscan {
+    pub fn new(max_range: f32) -> Self {
+        Self {
+            max_range,
+            penetration: 0,
+        }
+    }
+
+    pub fn penetration(self, penetration: u32) -> Self {
+        Self {
+            penetration,
+            ..self
+        }
+    }
+
+    pub fn fire_func(self) -> Box<dyn Fn(&mut WeaponArguments) + Send + Sync> {
+        Box::new(move |args: &mut WeaponArguments| {
+            let Some((origin, aim_direction)) = aim(args) else {
+                return;
+            };
+            let direction = bloom(args, aim_direction);
+            let (weapon, shooter) = (args.weapon, args.parent);
+            for origin in muzzle_origins(args, origin, aim_direction) {
+                args.commands.add(move |world: &mut World| {
+                    self.cast(world, weapon, shooter, origin, direction)
+                });
+            }
+        })
+    }
+
+    fn cast(
+        self,
+        world: &mut World,
+        weapon: Entity,
+        shooter: Entity,
+        origin: Vec2,
+        direction: Vec2,
+    ) {
+        let mut intersections = Vec::new();
+        world.resource::<RapierContext>().intersections_with_ray(
+            origin,
+            direction,
+            self.max_range,
+            true,
+            QueryFilter::new()
+                .exclude_sensors()
+                .exclude_rigid_body(shooter),
+            |entity, intersection| {
+                intersections.push((entity, intersection));
+                true
+            },
+        );
+        intersections.sort_by(|(_, a), (_, b)| a.time_of_impact.total_cmp(&b.time_of_impact));
+
+        let mut actors = world.query_filtered::<(), Hittable>();
+        let mut end = origin + direction * self.max_range;
+        let mut penetrated = 0;
+        for (impacted, intersection) in intersections {
+            world.send_event(HitscanHitEvent {
+                weapon,
+                shooter,
+                impacted,
+                point: intersection.point,
+                normal: intersection.normal,
+                direction,
+            });
+            if actors.get(world, impacted).is_err() || penetrated >= self.penetration {
+                end = intersection.point;
+                break;
+            }
+            penetrated += 1;
+        }
+        world.send_event(HitscanFiredEvent {
+            weapon,
+            origin,
+            end,
+        });
+    }
+}
+
+/// The hitscan counterpart to `ProjectileImpactEvent`, sent once per entity a ray hits, actor or
+/// not.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Event)]
+pub struct HitscanHitEvent {
+    pub weapon: Entity,
+    pub shooter: Entity,
+    pub impacted: Entity,
+    pub point: Vec2,
+    pub normal: Vec2,
+    pub direction: Vec2,
+}
+
+/// Sent for every hitscan shot with the point where the ray stopped, e.g. for drawing tracers.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Event)]
+pub struct HitscanFiredEvent {
+    pub weapon: Entity,
+    pub origin: Vec2,
+    pub end: Vec2,
+}
+
+/// Hitscan weapons with a `Knockback` push whatever they hit along the ray.
+pub(crate) fn knockback_from_hitscan(
+    mut hit_events: EventReader<HitscanHitEvent>,
+    mut knockback_events: EventWriter<KnockbackEvent>,
+    weapons: Query<&Knockback>,
+    modifiers: Query<&WeaponModifiers>,
+) {
+    for HitscanHitEvent {
+        weapon,
+        shooter,
+        impacted,
+        direction,
+        ..
+    } in hit_events.read()
+    {
+        let base = weapons
+            .get(*weapon)
+            .map_or(0., |Knockback(knockback)| *knockback);
+        let knockback =
+            ShotModifiers::of(&modifiers, *weapon, *shooter).apply(WeaponStat::Knockback, base);
+        if knockback > 0. {
+            knockback_events.send(KnockbackEvent {
+                entity: *impacted,
+                direction: *direction,
+                force: knockback,
+            });
+        }
+    }
+}
+
+/// Hitscan weapons with a `Damage` hurt everything their ray hits.
+pub(crate) fn damage_from_hitscan(
+    mut hit_events: EventReader<HitscanHitEvent>,
+    mut damage_events: EventWriter<DamageDealt>,
+    weapons: Query<&Damage>,
+    modifiers: Query<&WeaponModifiers>,
+    mut health: Query<&mut Health>,
+) {
+    for HitscanHitEvent {
+        weapon,
+        shooter,
+        impacted,
+        point,
+        ..
+    } in hit_events.read()
+    {
+        let base = weapons.get(*weapon).map_or(0., |Damage(damage)| *damage);
+        let damage =
+            ShotModifiers::of(&modifiers, *weapon, *shooter).apply(WeaponStat::Damage, base);
+        let Ok(mut health) = health.get_mut(*impacted) else {
+            continue;
+        };
+        if damage <= 0. {
+            continue;
+        }
+        health.0 -= damage;
+        damage_events.send(DamageDealt {
+            source: *weapon,
+            target: *impacted,
+            amount: damage,
+            position: *point,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::event::ManualEventReader, prelude::App};
+
+    use super::*;
+    use crate::{
+        testing::{sent, solid, weapon_physics_app, wielded},
+        weapons::{FireWeaponEvent, WeaponAction, WeaponFireMode},
+    };
+
+    /// Fires a hitscan weapon with `penetration` from the origin along +x at targets at x = 50 and
+    /// 100 and a wall at x = 150 with another target behind it. Returns the app, the targets in
+    /// order and where the ray stopped.
+    fn fire(penetration: u32) -> (App, Vec<Entity>, Vec2) {
+        let mut app = weapon_physics_app();
+        let world = app.world_mut();
+        let fire_func = Hitscan::new(500.).penetration(penetration).fire_func();
+        let weapon = wielded(world, fire_func, WeaponFireMode::SemiAuto);
+        world.entity_mut(weapon).insert(Damage(10.));
+        let mut targets = Vec::new();
+        for x in [50., 100., 200.] {
+            let target = solid(world, Vec2::new(x, 0.), 10.);
+            world.entity_mut(target).insert(Health(100.));
+            targets.push(target);
+        }
+        solid(world, Vec2::new(150., 0.), 10.);
+        let aim_point = world
+            .spawn(Transform2d::from_translation(Vec2::X * 300.))
+            .id();
+        app.update();
+
+        let mut fired = ManualEventReader::<HitscanFiredEvent>::default();
+        app.world_mut().send_event(FireWeaponEvent {
+            weapon,
+            target: Some(aim_point),
+            action: WeaponAction::Primary,
+        });
+        app.update();
+        app.update();
+        let end = sent(&app, &mut fired)[0].end;
+        (app, targets, end)
+    }
+
+    fn health(app: &App, targets: &[Entity]) -> Vec<f32> {
+        targets
+            .iter()
+            .map(|t| app.world().get::<Health>(*t).unwrap().0)
+            .collect()
+    }
+
+    #[test]
+    fn rays_stop_after_their_penetration() {
+        let (app, targets, end) = fire(0);
+        assert_eq!(health(&app, &targets), vec![90., 100., 100.]);
+        assert!(end.distance(Vec2::new(40., 0.)) < 1e-3);
+    }
+
+    #[test]
+    fn rays_stop_at_walls() {
+        let (app, targets, end) = fire(5);
+        assert_eq!(health(&app, &targets), vec![90., 90., 100.]);
+        assert!(end.distance(Vec2::new(140., 0.)) < 1e-3);
+    }
+}
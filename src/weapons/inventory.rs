@@ -0,0 +1,308 @@
+use bevy::{
+    prelude::{Component, Entity, Event, EventReader, EventWriter, Query, Res, With},
+    reflect::Reflect,
+    time::{Time, Timer, TimerMode},
+};
+
+use super::{
+    burst::BurstState,
+    charge::{ChargeProgressEvent, ChargeState},
+    Weapon,
+};
+
+/// The weapons an actor carries. Only the weapon in the active slot is fired by player input.
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct WeaponInventory {
+    pub slots: Vec<Entity>,
+    pub active: usize,
+    pub swap_delay: f32,
+    pub swap_timer: Timer,
+    pub swapping: bool,
+}
+
+impl WeaponInventory {
+    pub fn new(slots: Vec<Entity>, swap_delay: f32) -> Self {
+        Self {
+            slots,
+            active: 0,
+            swap_delay,
+            swap_timer: Timer::from_seconds(swap_delay, TimerMode::Once),
+            swapping: false,
+        }
+    }
+
+    pub fn active_weapon(&self) -> Option<Entity> {
+        self.slots.get(self.active).copied()
+    }
+
+    /// The active weapon, or `None` while the swap delay is still running.
+    pub fn ready_weapon(&self) -> Option<Entity> {
+        if self.swapping {
+            None
+        } else {
+            self.active_weapon()
+        }
+    }
+
+    pub fn push(&mut self, weapon: Entity) {
+        self.slots.push(weapon);
+    }
+
+    /// Takes `weapon` out of its slot. If it was the active weapon the next slot becomes active.
+    pub fn remove(&mut self, weapon: Entity) -> bool {
+        let Some(index) = self.slots.iter().position(|w| *w == weapon) else {
+            return false;
+        };
+        self.slots.remove(index);
+        if index < self.active || self.active >= self.slots.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+        true
+    }
+
+    fn resolve(&self, switch: WeaponSwitch) -> Option<usize> {
+        let len = self.slots.len();
+        if len == 0 {
+            return None;
+        }
+        match switch {
+            WeaponSwitch::Next => Some((self.active + 1) % len),
+            WeaponSwitch::Previous => Some((self.active + len - 1) % len),
+            WeaponSwitch::Slot(slot) if slot < len => Some(slot),
+            WeaponSwitch::Slot(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub enum WeaponSwitch {
+    Next,
+    Previous,
+    Slot(usize),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct SwitchWeaponEvent {
+    pub actor: Entity,
+    pub switch: WeaponSwitch,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct ActiveWeaponChangedEvent {
+    pub actor: Entity,
+    pub previous: Option<Entity>,
+    pub current: Option<Entity>,
+}
+
+/// Weapons that were despawned while in an inventory are taken out of their slots.
+pub(crate) fn prune_despawned_weapons(
+    mut inventories: Query<(Entity, &mut WeaponInventory)>,
+    mut changed_events: EventWriter<ActiveWeaponChangedEvent>,
+    weapons: Query<(), With<Weapon>>,
+) {
+    for (actor, mut inventory) in inventories.iter_mut() {
+        let despawned: Vec<Entity> = inventory
+            .slots
+            .iter()
+            .copied()
+            .filter(|w| !weapons.contains(*w))
+            .collect();
+        if despawned.is_empty() {
+            continue;
+        }
+        let previous = inventory.active_weapon();
+        for weapon in despawned {
+            inventory.remove(weapon);
+        }
+        if previous != inventory.active_weapon() {
+            changed_events.send(ActiveWeaponChangedEvent {
+                actor,
+                previous,
+                current: inventory.active_weapon(),
+            });
+        }
+    }
+}
+
+/// Switching away from a weapon cancels its burst and drops its charge.
+pub(crate) fn switch_weapons(
+    mut events: EventReader<SwitchWeaponEvent>,
+    mut changed_events: EventWriter<ActiveWeaponChangedEvent>,
+    mut progress_events: EventWriter<ChargeProgressEvent>,
+    mut inventories: Query<&mut WeaponInventory>,
+    mut bursts: Query<&mut BurstState>,
+    mut charges: Query<&mut ChargeState>,
+) {
+    for SwitchWeaponEvent { actor, switch } in events.read() {
+        let Ok(mut inventory) = inventories.get_mut(*actor) else {
+            continue;
+        };
+        let Some(slot) = inventory.resolve(*switch) else {
+            continue;
+        };
+        if slot == inventory.active {
+            continue;
+        }
+        let previous = inventory.active_weapon();
+        if let Some(weapon) = previous {
+            if let Ok(mut burst) = bursts.get_mut(weapon) {
+                burst.cancel();
+            }
+            if let Ok(mut charge) = charges.get_mut(weapon) {
+                if charge.charging {
+                    progress_events.send(ChargeProgressEvent { weapon, level: 0. });
+                }
+                charge.charging = false;
+                charge.elapsed = 0.;
+            }
+        }
+        inventory.active = slot;
+        inventory.swapping = true;
+        inventory.swap_timer = Timer::from_seconds(inventory.swap_delay, TimerMode::Once);
+        changed_events.send(ActiveWeaponChangedEvent {
+            actor: *actor,
+            previous,
+            current: inventory.active_weapon(),
+        });
+    }
+}
+
+pub(crate) fn tick_weapon_swaps(mut inventories: Query<&mut WeaponInventory>, time: Res<Time>) {
+    for mut inventory in inventories.iter_mut().filter(|i| i.swapping) {
+        inventory.swap_timer.tick(time.delta());
+        if inventory.swap_timer.finished() {
+            inventory.swapping = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        prelude::{App, Events, IntoSystemConfigs, Update, World},
+        MinimalPlugins,
+    };
+
+    use super::*;
+    use crate::weapons::{WeaponAction, WeaponFireMode};
+
+    fn weapon(world: &mut World) -> Entity {
+        world
+            .spawn(Weapon {
+                can_fire: true,
+                fire_func: Box::new(|_| ()),
+                fire_mode: WeaponFireMode::SemiAuto,
+            })
+            .id()
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<SwitchWeaponEvent>()
+            .add_event::<ActiveWeaponChangedEvent>()
+            .add_event::<ChargeProgressEvent>()
+            .add_systems(
+                Update,
+                (prune_despawned_weapons, switch_weapons, tick_weapon_swaps).chain(),
+            );
+        app
+    }
+
+    #[test]
+    fn switching_wraps_around_the_slots() {
+        let slots = vec![
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        ];
+        let inventory = WeaponInventory::new(slots, 0.);
+        assert_eq!(inventory.resolve(WeaponSwitch::Previous), Some(2));
+        assert_eq!(inventory.resolve(WeaponSwitch::Next), Some(1));
+        assert_eq!(inventory.resolve(WeaponSwitch::Slot(3)), None);
+    }
+
+    #[test]
+    fn removing_a_slot_keeps_the_active_weapon() {
+        let slots = vec![
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+            Entity::from_raw(3),
+        ];
+        let mut inventory = WeaponInventory::new(slots, 0.);
+        inventory.active = 1;
+        assert!(inventory.remove(Entity::from_raw(1)));
+        assert_eq!(inventory.active_weapon(), Some(Entity::from_raw(2)));
+        assert!(inventory.remove(Entity::from_raw(3)));
+        assert_eq!(inventory.active_weapon(), Some(Entity::from_raw(2)));
+        assert!(!inventory.remove(Entity::from_raw(3)));
+    }
+
+    #[test]
+    fn switching_cancels_the_burst_and_drops_the_charge() {
+        let mut app = app();
+        let (pistol, rifle) = (weapon(app.world_mut()), weapon(app.world_mut()));
+        app.world_mut().entity_mut(pistol).insert((
+            BurstState::new(3, 0.1, None, WeaponAction::Primary),
+            ChargeState {
+                elapsed: 0.5,
+                charging: true,
+                ..Default::default()
+            },
+        ));
+        let actor = app
+            .world_mut()
+            .spawn(WeaponInventory::new(vec![pistol, rifle], 0.2))
+            .id();
+        app.world_mut().send_event(SwitchWeaponEvent {
+            actor,
+            switch: WeaponSwitch::Next,
+        });
+        app.update();
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<WeaponInventory>(actor).unwrap().active_weapon(),
+            Some(rifle)
+        );
+        assert!(!world.get::<BurstState>(pistol).unwrap().in_progress());
+        let charge = world.get::<ChargeState>(pistol).unwrap();
+        assert!(!charge.charging);
+        assert_eq!(charge.elapsed, 0.);
+        let progress = world.resource::<Events<ChargeProgressEvent>>();
+        let progress: Vec<_> = progress.get_reader().read(progress).copied().collect();
+        assert_eq!(
+            progress,
+            [ChargeProgressEvent {
+                weapon: pistol,
+                level: 0.
+            }]
+        );
+    }
+
+    #[test]
+    fn despawned_weapons_are_pruned() {
+        let mut app = app();
+        let (pistol, rifle) = (weapon(app.world_mut()), weapon(app.world_mut()));
+        let actor = app
+            .world_mut()
+            .spawn(WeaponInventory::new(vec![pistol, rifle], 0.))
+            .id();
+        app.world_mut().despawn(pistol);
+        app.update();
+
+        let inventory = app.world().get::<WeaponInventory>(actor).unwrap();
+        assert_eq!(inventory.slots, [rifle]);
+        assert_eq!(inventory.active_weapon(), Some(rifle));
+        let changed = app.world().resource::<Events<ActiveWeaponChangedEvent>>();
+        let changed: Vec<_> = changed.get_reader().read(changed).copied().collect();
+        assert_eq!(
+            changed,
+            [ActiveWeaponChangedEvent {
+                actor,
+                previous: Some(pistol),
+                current: Some(rifle),
+            }]
+        );
+    }
+}
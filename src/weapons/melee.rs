@@ -0,0 +1,355 @@
+use bevy::{
+    prelude::{
+        Commands, Component, Entity, Event, EventReader, EventWriter, Parent, Query, Res, Vec2,
+        With, Without, World,
+    },
+    reflect::Reflect,
+    time::{Time, Timer, TimerMode},
+};
+use bevy_mod_transform2d::transform2d::Transform2d;
+use bevy_rapier2d::prelude::{Collider, QueryFilter, RapierContext, Sensor, Velocity};
+
+use crate::{
+    actors::{Faction, Hittable},
+    projectile::{KnockbackEvent, Projectile, Shooter},
+    stats::{Damage, DamageDealt, Health},
+};
+
+use super::{
+    modifiers::{ShotModifiers, WeaponModifiers, WeaponStat},
+    patterns::aim,
+    WeaponArguments,
+};
+
+/// Largest angle the blade moves between two overlap checks, so fast swings don't skip targets.
+const SWEEP_STEP: f32 = 0.15;
+
+/// A blade that sweeps an `arc` radians wide around the wielder's aim direction over the swing
+/// timer's duration, hitting each actor at most once per swing. Walls and other colliders that
+/// aren't actors stop nothing and get no hits.
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct Melee {
+    pub range: f32,
+    pub arc: f32,
+    pub width: f32,
+    pub knockback: f32,
+    /// Send enemy projectiles caught by the blade flying away from the wielder, as the wielder's
+    /// own. Projectiles without a `Faction` count as enemies.
+    pub deflect_projectiles: bool,
+    pub swing_timer: Timer,
+    pub swinging: bool,
+    /// Angle of the aim direction when the swing started.
+    pub facing: f32,
+    /// Offset of the point the blade swings around, like a `Muzzles` offset. Set from the shot
+    /// that started the swing.
+    pub pivot: Vec2,
+    /// Fraction of the arc covered so far.
+    pub swept: f32,
+    pub already_hit: Vec<Entity>,
+}
+
+impl Melee {
+    pub fn new(range: f32, arc: f32, duration: f32) -> Self {
+        Self {
+            range,
+            arc,
+            width: 6.,
+            knockback: 0.,
+            deflect_projectiles: false,
+            swing_timer: Timer::from_seconds(duration, TimerMode::Once),
+            swinging: false,
+            facing: 0.,
+            pivot: Vec2::ZERO,
+            swept: 0.,
+            already_hit: Vec::new(),
+        }
+    }
+
+    pub fn width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn knockback(self, knockback: f32) -> Self {
+        Self { knockback, ..self }
+    }
+
+    pub fn deflect_projectiles(self, deflect_projectiles: bool) -> Self {
+        Self {
+            deflect_projectiles,
+            ..self
+        }
+    }
+
+    /// The `Weapon::fire_func` for a weapon with a `Melee`: every shot starts a swing. The blade
+    /// swings around the first muzzle of the shot, if the weapon has `Muzzles`.
+    pub fn fire_func() -> Box<dyn Fn(&mut WeaponArguments) + Send + Sync> {
+        Box::new(|args: &mut WeaponArguments| {
+            let Some((_, direction)) = aim(args) else {
+                return;
+            };
+            let weapon = args.weapon;
+            let pivot = args.muzzles.first().copied().unwrap_or(Vec2::ZERO);
+            args.commands.add(move |world: &mut World| {
+                if let Some(mut melee) = world.get_mut::<Melee>(weapon) {
+                    melee.start(direction.to_angle(), pivot);
+                }
+            });
+        })
+    }
+
+    fn start(&mut self, facing: f32, pivot: Vec2) {
+        if self.swinging {
+            return;
+        }
+        self.swinging = true;
+        self.facing = facing;
+        self.pivot = pivot;
+        self.swept = 0.;
+        self.already_hit.clear();
+        self.swing_timer.reset();
+    }
+
+    fn blade_angle(&self, progress: f32) -> f32 {
+        self.facing + self.arc * (0.5 - progress)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Event)]
+pub struct MeleeHitEvent {
+    pub weapon: Entity,
+    pub wielder: Entity,
+    pub impacted: Entity,
+    pub direction: Vec2,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct ProjectileDeflectedEvent {
+    pub weapon: Entity,
+    pub projectile: Entity,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_melee_swings(
+    mut commands: Commands,
+    mut weapons: Query<(Entity, &mut Melee, &Parent)>,
+    transforms: Query<&Transform2d, Without<Projectile>>,
+    factions: Query<&Faction, Without<Projectile>>,
+    mut projectiles: Query<
+        (&mut Velocity, &mut Transform2d, Option<&mut Faction>),
+        With<Projectile>,
+    >,
+    targets: Query<(), Hittable>,
+    sensors: Query<(), With<Sensor>>,
+    mut hit_events: EventWriter<MeleeHitEvent>,
+    mut deflect_events: EventWriter<ProjectileDeflectedEvent>,
+    mut knockback_events: EventWriter<KnockbackEvent>,
+    modifiers: Query<&WeaponModifiers>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    for (weapon, mut melee, parent) in weapons.iter_mut().filter(|(_, m, _)| m.swinging) {
+        let wielder = parent.get();
+        let Ok(position) = transforms.get(wielder).map(|t| t.translation) else {
+            continue;
+        };
+        let origin = position + Vec2::from_angle(melee.facing).rotate(melee.pivot);
+        let wielder_faction = factions.get(wielder).ok().copied();
+        let knockback = ShotModifiers::of(&modifiers, weapon, wielder)
+            .apply(WeaponStat::Knockback, melee.knockback);
+        melee.swing_timer.tick(time.delta());
+        let progress = melee.swing_timer.fraction();
+
+        let blade = Collider::cuboid(melee.range / 2., melee.width / 2.);
+        // Piercing projectiles are sensors, but can still be deflected.
+        let deflectable =
+            |entity: Entity| !sensors.contains(entity) || projectiles.contains(entity);
+        let filter = QueryFilter::new()
+            .exclude_rigid_body(wielder)
+            .predicate(&deflectable);
+        let delta = progress - melee.swept;
+        let steps = ((delta * melee.arc.abs() / SWEEP_STEP).ceil() as usize).max(1);
+        let mut touched = Vec::new();
+        for step in 1..=steps {
+            let angle = melee.blade_angle(melee.swept + delta * step as f32 / steps as f32);
+            rapier_context.intersections_with_shape(
+                origin + Vec2::from_angle(angle) * melee.range / 2.,
+                angle,
+                &blade,
+                filter,
+                |entity| {
+                    touched.push(entity);
+                    true
+                },
+            );
+        }
+        melee.swept = progress;
+
+        for impacted in touched {
+            if melee.already_hit.contains(&impacted) {
+                continue;
+            }
+            melee.already_hit.push(impacted);
+            let away = |position: Vec2| {
+                (position - origin)
+                    .try_normalize()
+                    .unwrap_or(Vec2::from_angle(melee.facing))
+            };
+            if let Ok((mut velocity, mut transform, faction)) = projectiles.get_mut(impacted) {
+                let hostile = match faction.as_deref() {
+                    Some(faction) => wielder_faction
+                        .unwrap_or(Faction::FriendlyToAll)
+                        .is_hostile_to(faction),
+                    None => true,
+                };
+                if melee.deflect_projectiles && hostile {
+                    let direction = away(transform.translation);
+                    velocity.linvel = direction * velocity.linvel.length();
+                    transform.rotation = direction.to_angle();
+                    commands.entity(impacted).insert(Shooter(wielder));
+                    match (faction, wielder_faction) {
+                        (Some(mut faction), Some(wielder_faction)) => *faction = wielder_faction,
+                        (None, Some(wielder_faction)) => {
+                            commands.entity(impacted).insert(wielder_faction);
+                        }
+                        (Some(_), None) => {
+                            commands.entity(impacted).remove::<Faction>();
+                        }
+                        (None, None) => (),
+                    }
+                    deflect_events.send(ProjectileDeflectedEvent {
+                        weapon,
+                        projectile: impacted,
+                    });
+                }
+                continue;
+            }
+            if !targets.contains(impacted) {
+                continue;
+            }
+            let direction = transforms
+                .get(impacted)
+                .map_or(Vec2::from_angle(melee.facing), |t| away(t.translation));
+            hit_events.send(MeleeHitEvent {
+                weapon,
+                wielder,
+                impacted,
+                direction,
+            });
+            if knockback > 0. {
+                knockback_events.send(KnockbackEvent {
+                    entity: impacted,
+                    direction,
+                    force: knockback,
+                });
+            }
+        }
+
+        if melee.swing_timer.finished() {
+            melee.swinging = false;
+        }
+    }
+}
+
+/// Melee weapons with a `Damage` hurt everything their blade touches.
+pub(crate) fn damage_from_melee(
+    mut hit_events: EventReader<MeleeHitEvent>,
+    mut damage_events: EventWriter<DamageDealt>,
+    weapons: Query<&Damage>,
+    modifiers: Query<&WeaponModifiers>,
+    mut targets: Query<(&mut Health, &Transform2d)>,
+) {
+    for MeleeHitEvent {
+        weapon,
+        wielder,
+        impacted,
+        ..
+    } in hit_events.read()
+    {
+        let base = weapons.get(*weapon).map_or(0., |Damage(damage)| *damage);
+        let damage =
+            ShotModifiers::of(&modifiers, *weapon, *wielder).apply(WeaponStat::Damage, base);
+        let Ok((mut health, transform)) = targets.get_mut(*impacted) else {
+            continue;
+        };
+        if damage <= 0. {
+            continue;
+        }
+        health.0 -= damage;
+        damage_events.send(DamageDealt {
+            source: *weapon,
+            target: *impacted,
+            amount: damage,
+            position: transform.translation,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::event::ManualEventReader,
+        prelude::{GlobalTransform, Transform},
+    };
+    use bevy_rapier2d::prelude::RigidBody;
+
+    use super::*;
+    use crate::{
+        projectile::{ProjectileBundle, ProjectileImpactBehavior},
+        testing::{sent, solid, weapon_physics_app, wielded},
+        weapons::{FireWeaponEvent, WeaponAction, WeaponFireMode},
+    };
+
+    #[test]
+    fn swings_hit_actors_and_deflect_piercing_projectiles_but_not_walls() {
+        let mut app = weapon_physics_app();
+        let world = app.world_mut();
+        let weapon = wielded(world, Melee::fire_func(), WeaponFireMode::SemiAuto);
+        let melee = Melee::new(40., 1., 0.125).deflect_projectiles(true);
+        world.entity_mut(weapon).insert((melee, Damage(5.)));
+        let wielder = world.get::<Parent>(weapon).unwrap().get();
+        let enemy = solid(world, Vec2::new(30., -8.), 5.);
+        world.entity_mut(enemy).insert(Health(100.));
+        let wall = solid(world, Vec2::new(30., 8.), 5.);
+        let translation = Vec2::new(20., 0.);
+        let bullet = world
+            .spawn(ProjectileBundle {
+                projectile: Projectile {
+                    on_hit: ProjectileImpactBehavior::Pierce,
+                    ..Projectile::default()
+                },
+                transform: Transform2d::from_translation(translation),
+                _transform: Transform::from_translation(translation.extend(0.)),
+                global_transform: GlobalTransform::default(),
+                rigidbody: RigidBody::KinematicVelocityBased,
+                velocity: Velocity::linear(Vec2::new(-1., 0.)),
+                ..ProjectileBundle::default()
+            })
+            .id();
+        let aim_point = world
+            .spawn(Transform2d::from_translation(Vec2::X * 100.))
+            .id();
+        app.update();
+
+        let mut hits = ManualEventReader::<MeleeHitEvent>::default();
+        let mut deflections = ManualEventReader::<ProjectileDeflectedEvent>::default();
+        app.world_mut().send_event(FireWeaponEvent {
+            weapon,
+            target: Some(aim_point),
+            action: WeaponAction::Primary,
+        });
+        let mut hit = Vec::new();
+        let mut deflected = Vec::new();
+        for _ in 0..12 {
+            app.update();
+            hit.extend(sent(&app, &mut hits).iter().map(|e| e.impacted));
+            deflected.extend(sent(&app, &mut deflections).iter().map(|e| e.projectile));
+        }
+
+        assert_eq!(hit, vec![enemy]);
+        assert!(!hit.contains(&wall));
+        assert_eq!(app.world().get::<Health>(enemy).unwrap().0, 95.);
+        assert_eq!(deflected, vec![bullet]);
+        assert!(app.world().get::<Velocity>(bullet).unwrap().linvel.x > 0.);
+        assert_eq!(app.world().get::<Shooter>(bullet).unwrap().0, wielder);
+    }
+}
@@ -0,0 +1,955 @@
+use bevy::prelude::Reflect;
+use std::{marker::PhantomData, time::Duration};
+
+use bevy::{
+    ecs::system::SystemId,
+    prelude::{
+        in_state, not, App, AssetApp, Commands, Component, Entity, Event, EventReader, EventWriter,
+        IntoSystemConfigs, IntoSystemSetConfigs, Mut, OnExit, Parent, Plugin, Query, Res, ResMut,
+        SystemSet, Update, Vec2, With,
+    },
+    time::{Time, Timer, TimerMode},
+};
+use bevy_mod_transform2d::transform2d::Transform2d;
+use bevy_turborand::prelude::{GlobalRng, RngComponent};
+use serde::Deserialize;
+
+use crate::{
+    actors::Faction,
+    meta_states::PluginControlState,
+    player::CursorTracker,
+    projectile::Knockback,
+    stats::{Damage, DamageDealt},
+};
+
+use self::{
+    aim_assist::{acquire_aim_assist_targets, AimAssist},
+    ammo::{
+        auto_reload_empty_weapons, start_reloads, tick_reloads, EmptyClickEvent, Magazine, Reload,
+        ReloadFinishedEvent, ReloadStartedEvent, ReloadWeaponEvent,
+    },
+    beam::{
+        apply_beam_hits, clear_beams, despawn_orphaned_beam_segments, update_beams, Beam,
+        BeamHitEvent,
+    },
+    burst::{tick_bursts, BurstState},
+    charge::{
+        release_charges, start_charges, tick_charges, ChargeProgressEvent, ChargeState,
+        ReleaseChargeEvent, StartChargeEvent,
+    },
+    definition::{apply_weapon_definitions, WeaponDefinition, WeaponDefinitionLoader},
+    heat::{update_heat, Heat, OverheatEndedEvent, OverheatStartedEvent},
+    hitscan::{damage_from_hitscan, knockback_from_hitscan, HitscanFiredEvent, HitscanHitEvent},
+    inventory::{
+        prune_despawned_weapons, switch_weapons, tick_weapon_swaps, ActiveWeaponChangedEvent,
+        SwitchWeaponEvent, WeaponInventory,
+    },
+    melee::{
+        damage_from_melee, update_melee_swings, Melee, MeleeHitEvent, ProjectileDeflectedEvent,
+    },
+    modifiers::{ShotModifiers, WeaponModifiers, WeaponStat},
+    muzzle::{MuzzleCycling, Muzzles},
+    patterns::FirePattern,
+    pickup::{
+        add_pickup_sensors, drop_weapons, pick_up_weapons, track_pickup_reach, DropWeaponEvent,
+        PickUpWeaponEvent, WeaponDroppedEvent, WeaponPickedUpEvent, WeaponPickup,
+    },
+    recoil::{apply_recoil, recover_bloom, AimSpread, Recoil},
+};
+
+pub mod aim_assist;
+pub mod ammo;
+pub mod beam;
+pub mod burst;
+pub mod charge;
+pub mod definition;
+pub mod heat;
+pub mod hitscan;
+pub mod inventory;
+pub mod melee;
+pub mod modifiers;
+pub mod muzzle;
+pub mod patterns;
+pub mod pickup;
+pub mod recoil;
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub struct WeaponPlugin<T: PluginControlState> {
+    _z: PhantomData<T>,
+}
+
+impl<T: PluginControlState> Default for WeaponPlugin<T> {
+    fn default() -> Self {
+        Self { _z: PhantomData }
+    }
+}
+
+impl<T: PluginControlState> Plugin for WeaponPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FireWeaponEvent>()
+            .add_event::<WeaponFiredEvent>()
+            .add_event::<WeaponFireRejected>()
+            .add_event::<ReloadWeaponEvent>()
+            .add_event::<EmptyClickEvent>()
+            .add_event::<ReloadStartedEvent>()
+            .add_event::<ReloadFinishedEvent>()
+            .add_event::<StartChargeEvent>()
+            .add_event::<ReleaseChargeEvent>()
+            .add_event::<ChargeProgressEvent>()
+            .add_event::<SwitchWeaponEvent>()
+            .add_event::<ActiveWeaponChangedEvent>()
+            .add_event::<HitscanHitEvent>()
+            .add_event::<HitscanFiredEvent>()
+            .add_event::<BeamHitEvent>()
+            .add_event::<MeleeHitEvent>()
+            .add_event::<ProjectileDeflectedEvent>()
+            .add_event::<OverheatStartedEvent>()
+            .add_event::<OverheatEndedEvent>()
+            .add_event::<PickUpWeaponEvent>()
+            .add_event::<DropWeaponEvent>()
+            .add_event::<WeaponPickedUpEvent>()
+            .add_event::<WeaponDroppedEvent>()
+            .add_event::<DamageDealt>();
+        app.init_asset::<WeaponDefinition>()
+            .init_asset_loader::<WeaponDefinitionLoader>();
+        app.register_type::<Magazine>()
+            .register_type::<Reload>()
+            .register_type::<BurstState>()
+            .register_type::<ChargeState>()
+            .register_type::<WeaponInventory>()
+            .register_type::<WeaponDefinition>()
+            .register_type::<FirePattern>()
+            .register_type::<Beam>()
+            .register_type::<Melee>()
+            .register_type::<Heat>()
+            .register_type::<Recoil>()
+            .register_type::<AimSpread>()
+            .register_type::<AimAssist>()
+            .register_type::<WeaponPickup>()
+            .register_type::<Muzzles>()
+            .register_type::<MuzzleCycling>()
+            .register_type::<WeaponFireMode>()
+            .register_type::<WeaponAction>()
+            .register_type::<FireRejectReason>();
+        app.configure_sets(
+            Update,
+            WeaponTriggerSet
+                .after(enable_weapons_on_cooldown)
+                .after(tick_secondary_cooldowns)
+                .before(fire_weapons),
+        );
+        app.add_systems(
+            Update,
+            (
+                fire_weapons
+                    .after(enable_weapons_on_cooldown)
+                    .after(tick_secondary_cooldowns),
+                tick_cooldowns,
+                tick_secondary_cooldowns,
+                tick_bursts.before(fire_weapons),
+                (start_charges, tick_charges, release_charges)
+                    .chain()
+                    .after(WeaponTriggerSet)
+                    .before(fire_weapons),
+                enable_weapons_on_cooldown.after(tick_cooldowns),
+                auto_reload_empty_weapons.after(fire_weapons),
+                start_reloads.after(auto_reload_empty_weapons),
+                tick_reloads.after(start_reloads),
+                (prune_despawned_weapons, switch_weapons, tick_weapon_swaps).chain(),
+                apply_weapon_definitions,
+                (knockback_from_hitscan, damage_from_hitscan),
+                (update_beams, apply_beam_hits).chain().after(fire_weapons),
+                despawn_orphaned_beam_segments,
+                (update_melee_swings, damage_from_melee)
+                    .chain()
+                    .after(fire_weapons),
+                update_heat.after(fire_weapons),
+                (apply_recoil, recover_bloom).chain().after(fire_weapons),
+                (
+                    add_pickup_sensors,
+                    (track_pickup_reach, pick_up_weapons).chain(),
+                    drop_weapons.after(fire_weapons),
+                ),
+                acquire_aim_assist_targets.before(WeaponTriggerSet),
+            )
+                .run_if(in_state(T::active_state())),
+        );
+        app.add_systems(
+            Update,
+            reject_inactive_fire_requests.run_if(not(in_state(T::active_state()))),
+        );
+        app.add_systems(OnExit(T::active_state()), clear_beams);
+    }
+}
+
+/// Systems that pull weapon triggers by sending `FireWeaponEvent`s or starting and releasing
+/// charges, like the player's mouse buttons and `ShooterAI`. They run after cooldowns are ticked
+/// and before the shots are fired, so a held trigger fires on the very frame its weapon is ready
+/// again. Put your own input and AI systems in it too.
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct WeaponTriggerSet;
+
+#[derive(Component)]
+pub struct Weapon {
+    pub can_fire: bool,
+    pub fire_func: Box<dyn Fn(&mut WeaponArguments) + Send + Sync>,
+    pub fire_mode: WeaponFireMode,
+}
+
+impl Weapon {
+    /// A weapon whose shots run a registered one-shot system instead of a closure, so the fire
+    /// logic can use any system parameters it needs:
+    ///
+    /// ```ignore
+    /// fn fire_rocket(In(shot): In<WeaponFireInput>, velocities: Query<&Velocity>, ...) { ... }
+    ///
+    /// let fire_rocket = app.world_mut().register_system(fire_rocket);
+    /// commands.spawn((Weapon::from_system(fire_rocket, WeaponFireMode::SemiAuto), ...));
+    /// ```
+    pub fn from_system(system: SystemId<WeaponFireInput>, fire_mode: WeaponFireMode) -> Self {
+        Self {
+            can_fire: true,
+            fire_func: Box::new(move |args: &mut WeaponArguments| {
+                let input = WeaponFireInput::new(args);
+                args.commands.run_system_with_input(system, input);
+            }),
+            fire_mode,
+        }
+    }
+
+    /// Fire mode of one of the weapon's actions and whether it's off cooldown, or `None` if the
+    /// weapon has no such action.
+    pub fn action(
+        &self,
+        secondary: Option<&SecondaryFire>,
+        action: WeaponAction,
+    ) -> Option<(WeaponFireMode, bool)> {
+        match action {
+            WeaponAction::Primary => Some((self.fire_mode, self.can_fire)),
+            WeaponAction::Secondary => secondary.map(|s| (s.fire_mode, s.can_fire)),
+        }
+    }
+}
+
+/// An alternate action on a `Weapon`, like an underbarrel grenade launcher, with its own fire
+/// mode and cooldown. It shares the weapon's magazine, heat and recoil, and a burst or charge of
+/// one action blocks the other until it's done.
+#[derive(Component)]
+pub struct SecondaryFire {
+    pub can_fire: bool,
+    pub fire_func: Box<dyn Fn(&mut WeaponArguments) + Send + Sync>,
+    pub fire_mode: WeaponFireMode,
+    pub cooldown: Cooldown,
+}
+
+impl SecondaryFire {
+    pub fn new(
+        fire_func: Box<dyn Fn(&mut WeaponArguments) + Send + Sync>,
+        fire_mode: WeaponFireMode,
+        cooldown: f32,
+    ) -> Self {
+        Self {
+            can_fire: true,
+            fire_func,
+            fire_mode,
+            cooldown: Cooldown::new(cooldown),
+        }
+    }
+}
+
+/// Which of a weapon's actions a `FireWeaponEvent` triggers.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Default)]
+pub enum WeaponAction {
+    #[default]
+    Primary,
+    /// Fires the weapon's `SecondaryFire`, if it has one.
+    Secondary,
+}
+
+/// What a fire system registered with `Weapon::from_system` gets passed for every shot: the same
+/// as a `WeaponArguments`, minus the commands, queries and rng. Keep the two in step.
+#[derive(Clone, Debug)]
+pub struct WeaponFireInput {
+    pub weapon: Entity,
+    pub parent: Entity,
+    pub target: Option<Entity>,
+    pub cursor: Entity,
+    pub charge: f32,
+    pub bloom: f32,
+    pub assist: f32,
+    pub faction: Option<Faction>,
+    pub modifiers: ShotModifiers,
+    pub knockback: f32,
+    pub damage: f32,
+    /// The muzzles this shot comes out of, already picked by `Muzzles::fire`.
+    pub muzzles: Vec<Vec2>,
+    pub shot_age: f32,
+}
+
+impl WeaponFireInput {
+    pub fn new(args: &WeaponArguments) -> Self {
+        Self {
+            weapon: args.weapon,
+            parent: args.parent,
+            target: args.target,
+            cursor: args.cursor,
+            charge: args.charge,
+            bloom: args.bloom,
+            assist: args.assist,
+            faction: args.faction,
+            modifiers: args.modifiers.clone(),
+            knockback: args.knockback,
+            damage: args.damage,
+            muzzles: args.muzzles.clone(),
+            shot_age: args.shot_age,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Deserialize)]
+pub enum WeaponFireMode {
+    SemiAuto,
+    FullAuto,
+    /// One trigger pull fires `count` shots, `interval` seconds apart. The cooldown starts over
+    /// with every shot, so it only runs out once the whole burst is done.
+    Burst {
+        count: u32,
+        interval: f32,
+    },
+    /// Hold to charge for up to `max_charge` seconds, release to fire. Releasing below the
+    /// normalized `min_charge` fizzles; `auto_release` fires on its own once fully charged.
+    Charge {
+        max_charge: f32,
+        min_charge: f32,
+        auto_release: bool,
+    },
+}
+
+/// Everything a `Weapon::fire_func` gets for a shot. New fields should be passed on to fire
+/// systems in `WeaponFireInput` too.
+pub struct WeaponArguments<'c, 'w, 's, 'c2, 'w2, 's2> {
+    pub commands: &'c mut Commands<'w, 's>,
+    pub weapon: Entity,
+    pub cursor: Entity,
+    pub target: Option<Entity>,
+    pub parent: Entity,
+    pub transforms: Query<'c2, 'w2, &'s2 Transform2d>,
+    /// Normalized charge level of a `WeaponFireMode::Charge` weapon, `1` for every other weapon.
+    pub charge: f32,
+    /// Extra aim spread in radians from the weapon's `Recoil` and the wielder's `AimSpread`.
+    pub bloom: f32,
+    /// How far the aim is bent from the cursor toward `target`, from the wielder's `AimAssist`.
+    /// `1` for wielders without one, who aim straight at their target.
+    pub assist: f32,
+    pub rng: RngComponent,
+    /// The wielder's `Faction`, which `fire_pattern` gives every projectile it spawns.
+    pub faction: Option<Faction>,
+    /// Upgrades from the `WeaponModifiers` on the weapon and its wielder.
+    pub modifiers: ShotModifiers,
+    /// The weapon's `Knockback` and `Damage` with modifiers applied, `0` for weapons without
+    /// them. `fire_pattern` gives them to every projectile it spawns.
+    pub knockback: f32,
+    pub damage: f32,
+    /// Offsets of the `Muzzles` this shot comes out of, empty for weapons without any.
+    pub muzzles: Vec<Vec2>,
+    /// Seconds between when the shot was due and the end of the frame. A weapon firing faster
+    /// than the frame rate fires several shots per frame, and each should start out as far along
+    /// as it would have got had it been fired on time.
+    pub shot_age: f32,
+}
+
+/// Most shots a single `FireWeaponEvent` can fire, so a long frame doesn't unload a fast weapon
+/// all at once.
+const MAX_SHOTS_PER_FRAME: usize = 32;
+
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct Cooldown {
+    pub max: f32,
+    pub timer: Timer,
+    /// Seconds since the timer ran out.
+    pub overshoot: f32,
+}
+
+impl Cooldown {
+    pub fn new(max: f32) -> Self {
+        Self {
+            max,
+            timer: Timer::from_seconds(max, TimerMode::Once),
+            overshoot: 0.,
+        }
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        let remaining = self.timer.remaining_secs();
+        self.timer.tick(delta);
+        if self.timer.finished() {
+            self.overshoot += delta.as_secs_f32() - remaining;
+        }
+    }
+
+    /// Start the cooldown over for a trigger that fires as fast as the weapon allows, such as a
+    /// held `WeaponFireMode::FullAuto` one. Returns how long ago each shot that came due in the
+    /// last `window` seconds would have gone off, oldest first. Time left over after the last of
+    /// them counts toward the new cooldown.
+    ///
+    /// Only a cooldown that ran out within the window carries time over. One that ran out before
+    /// it has been sitting idle with the trigger released, so the first shot of the new pull goes
+    /// off now.
+    pub fn restart_continuous(&mut self, duration: f32, window: f32) -> Vec<f32> {
+        let mut age = if self.overshoot <= window {
+            self.overshoot.max(0.)
+        } else {
+            0.
+        };
+        let mut ages = vec![age];
+        while duration > 0. && age >= duration && ages.len() < MAX_SHOTS_PER_FRAME {
+            age -= duration;
+            ages.push(age);
+        }
+        self.restart(duration, age);
+        ages
+    }
+
+    /// Start the cooldown over, `carry` seconds in.
+    pub fn restart(&mut self, duration: f32, carry: f32) {
+        self.timer = Timer::from_seconds(duration, TimerMode::Once);
+        self.timer
+            .set_elapsed(Duration::from_secs_f32(carry.clamp(0., duration)));
+        self.overshoot = 0.;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct FireWeaponEvent {
+    pub weapon: Entity,
+    pub target: Option<Entity>,
+    pub action: WeaponAction,
+}
+
+/// Sent by `fire_weapons` for every shot that actually went off.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct WeaponFiredEvent {
+    pub weapon: Entity,
+    pub wielder: Entity,
+    pub target: Option<Entity>,
+    pub action: WeaponAction,
+}
+
+/// Why `fire_weapons` dropped a `FireWeaponEvent` without firing.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub enum FireRejectReason {
+    /// The weapon was despawned, or the entity never was a weapon.
+    NoWeapon,
+    /// The weapon isn't held by anyone, e.g. because it's lying on the ground.
+    NoParent,
+    /// The weapon has no `SecondaryFire` for a `WeaponAction::Secondary`.
+    NoSuchAction,
+    OnCooldown,
+    /// The magazine was empty. The dry fire still cycles the cooldown.
+    NoAmmo,
+    Reloading,
+    Overheated,
+    /// A burst is still running and only takes the shots it queued itself.
+    BurstInProgress,
+    /// The plugin's control state isn't active.
+    InactiveState,
+}
+
+/// Sent by `fire_weapons` for every `FireWeaponEvent` it drops instead of firing.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct WeaponFireRejected {
+    pub weapon: Entity,
+    pub action: WeaponAction,
+    pub reason: FireRejectReason,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn fire_weapons(
+    mut events: EventReader<FireWeaponEvent>,
+    mut fired_events: EventWriter<WeaponFiredEvent>,
+    mut empty_events: EventWriter<EmptyClickEvent>,
+    mut rejected_events: EventWriter<WeaponFireRejected>,
+    mut commands: Commands,
+    cursor: Query<Entity, With<CursorTracker>>,
+    mut weapons: Query<(
+        &mut Weapon,
+        Option<&mut Cooldown>,
+        Option<&mut SecondaryFire>,
+        Option<&Parent>,
+        Option<&mut Magazine>,
+        Option<&Reload>,
+        Option<&mut BurstState>,
+        Option<&mut ChargeState>,
+        Option<&mut Heat>,
+        Option<&Recoil>,
+        Option<&mut Muzzles>,
+        Option<&Knockback>,
+        Option<&Damage>,
+    )>,
+    transforms: Query<&Transform2d>,
+    spreads: Query<&AimSpread>,
+    assists: Query<&AimAssist>,
+    factions: Query<&Faction>,
+    modifiers: Query<&WeaponModifiers>,
+    mut global_rng: ResMut<GlobalRng>,
+    time: Res<Time>,
+) {
+    let mut args = WeaponArguments {
+        commands: &mut commands,
+        weapon: Entity::PLACEHOLDER,
+        cursor: cursor.get_single().unwrap_or(Entity::PLACEHOLDER),
+        target: None,
+        parent: Entity::PLACEHOLDER,
+        transforms,
+        charge: 1.,
+        bloom: 0.,
+        assist: 1.,
+        rng: RngComponent::from(&mut global_rng),
+        faction: None,
+        modifiers: ShotModifiers::default(),
+        knockback: 0.,
+        damage: 0.,
+        muzzles: Vec::new(),
+        shot_age: 0.,
+    };
+    for FireWeaponEvent {
+        weapon,
+        target,
+        action,
+    } in events.read()
+    {
+        let mut reject = |reason| {
+            rejected_events.send(WeaponFireRejected {
+                weapon: *weapon,
+                action: *action,
+                reason,
+            });
+        };
+        let Ok((
+            mut weapon_component,
+            cooldown,
+            mut secondary,
+            parent,
+            mut magazine,
+            reload,
+            mut burst,
+            mut charge,
+            mut heat,
+            recoil,
+            mut muzzles,
+            knockback,
+            damage,
+        )) = weapons.get_mut(*weapon)
+        else {
+            reject(FireRejectReason::NoWeapon);
+            continue;
+        };
+        let Some(wielder) = parent.map(|p| p.get()) else {
+            reject(FireRejectReason::NoParent);
+            continue;
+        };
+        let Some((fire_mode, can_fire)) = weapon_component.action(secondary.as_deref(), *action)
+        else {
+            reject(FireRejectReason::NoSuchAction);
+            continue;
+        };
+        if burst.as_ref().is_some_and(|b| !b.accepts_shot(*action)) {
+            reject(FireRejectReason::BurstInProgress);
+            continue;
+        }
+        let blocked = if reload.is_some_and(|r| r.reloading) {
+            Some(FireRejectReason::Reloading)
+        } else if heat.as_ref().is_some_and(|h| h.overheated) {
+            Some(FireRejectReason::Overheated)
+        } else {
+            None
+        };
+        if let Some(reason) = blocked {
+            if let Some(burst) = burst.as_mut() {
+                burst.cancel();
+            }
+            reject(reason);
+            continue;
+        }
+        // Follow-up shots of a burst go off while the cooldown restarted by the previous one runs.
+        if !can_fire && !burst.as_ref().is_some_and(|b| b.in_progress()) {
+            reject(FireRejectReason::OnCooldown);
+            continue;
+        }
+
+        args.modifiers =
+            ShotModifiers::collect(modifiers.get(*weapon).ok(), modifiers.get(wielder).ok());
+        args.knockback = args
+            .modifiers
+            .apply(WeaponStat::Knockback, knockback.map_or(0., |k| k.0));
+        args.damage = args
+            .modifiers
+            .apply(WeaponStat::Damage, damage.map_or(0., |d| d.0));
+        let cooldown = match (action, secondary.as_mut()) {
+            (WeaponAction::Secondary, Some(secondary)) => {
+                secondary.can_fire = false;
+                Some(&mut secondary.cooldown)
+            }
+            _ => {
+                if cooldown.is_some() {
+                    weapon_component.can_fire = false;
+                }
+                cooldown.map(Mut::into_inner)
+            }
+        };
+        let shot_ages = match cooldown {
+            Some(cooldown) => {
+                let duration = args
+                    .modifiers
+                    .apply(WeaponStat::Cooldown, cooldown.max)
+                    .max(0.);
+                if fire_mode == WeaponFireMode::FullAuto {
+                    cooldown.restart_continuous(duration, time.delta_seconds())
+                } else {
+                    cooldown.restart(duration, 0.);
+                    vec![0.]
+                }
+            }
+            None => vec![0.],
+        };
+
+        for (shot, shot_age) in shot_ages.into_iter().enumerate() {
+            if shot > 0 && heat.as_ref().is_some_and(|h| h.heat >= h.max) {
+                break;
+            }
+            if let Some(magazine) = magazine.as_mut() {
+                if magazine.is_empty() {
+                    if shot == 0 {
+                        empty_events.send(EmptyClickEvent { weapon: *weapon });
+                        if let Some(burst) = burst.as_mut() {
+                            burst.cancel();
+                        }
+                        reject(FireRejectReason::NoAmmo);
+                    }
+                    break;
+                }
+                magazine.loaded -= 1;
+            }
+            match (fire_mode, burst.as_mut()) {
+                (_, Some(burst)) if burst.in_progress() => burst.queued -= 1,
+                (WeaponFireMode::Burst { count, interval }, Some(burst)) => {
+                    **burst = BurstState::new(count, interval, *target, *action);
+                }
+                (WeaponFireMode::Burst { count, interval }, None) => {
+                    args.commands
+                        .entity(*weapon)
+                        .insert(BurstState::new(count, interval, *target, *action));
+                }
+                _ => (),
+            }
+            args.charge = match (fire_mode, charge.as_mut()) {
+                (WeaponFireMode::Charge { max_charge, .. }, Some(charge))
+                    if charge.action == *action =>
+                {
+                    let level = charge.level(max_charge);
+                    charge.elapsed = 0.;
+                    level
+                }
+                _ => 1.,
+            };
+            if let Some(heat) = heat.as_mut() {
+                heat.heat += heat.per_shot;
+            }
+            args.bloom = recoil.map_or(0., |r| r.bloom)
+                + spreads.get(wielder).map_or(0., |AimSpread(spread)| *spread);
+            args.muzzles = muzzles.as_mut().map_or(Vec::new(), |m| m.fire());
+            args.assist = assists.get(wielder).map_or(1., |a| a.strength);
+            args.faction = factions.get(wielder).ok().copied();
+            args.shot_age = shot_age;
+            args.weapon = *weapon;
+            args.target = *target;
+            args.parent = wielder;
+            let fire_func = match (action, secondary.as_deref()) {
+                (WeaponAction::Secondary, Some(secondary)) => &secondary.fire_func,
+                _ => &weapon_component.fire_func,
+            };
+            (*fire_func)(&mut args);
+            fired_events.send(WeaponFiredEvent {
+                weapon: *weapon,
+                wielder,
+                target: *target,
+                action: *action,
+            });
+        }
+    }
+}
+
+/// Fire requests that come in while the plugin is inactive are never going to be handled.
+fn reject_inactive_fire_requests(
+    mut events: EventReader<FireWeaponEvent>,
+    mut rejected_events: EventWriter<WeaponFireRejected>,
+) {
+    for FireWeaponEvent { weapon, action, .. } in events.read() {
+        rejected_events.send(WeaponFireRejected {
+            weapon: *weapon,
+            action: *action,
+            reason: FireRejectReason::InactiveState,
+        });
+    }
+}
+
+fn enable_weapons_on_cooldown(
+    mut weapon_query: Query<(&mut Weapon, &mut Cooldown, Option<&Heat>)>,
+) {
+    for (mut weapon, _, heat) in weapon_query
+        .iter_mut()
+        .filter(|(w, c, _)| (!w.can_fire) && c.timer.finished())
+    {
+        if heat.is_some_and(|h| h.overheated) {
+            continue;
+        }
+        weapon.can_fire = true;
+    }
+}
+
+fn tick_cooldowns(mut cooldown_query: Query<&mut Cooldown>, time: Res<Time>) {
+    for mut cooldown in cooldown_query.iter_mut() {
+        cooldown.tick(time.delta());
+    }
+}
+
+fn tick_secondary_cooldowns(
+    mut secondaries: Query<(&mut SecondaryFire, Option<&Heat>)>,
+    time: Res<Time>,
+) {
+    for (mut secondary, heat) in secondaries.iter_mut() {
+        secondary.cooldown.tick(time.delta());
+        if !secondary.can_fire
+            && secondary.cooldown.timer.finished()
+            && !heat.is_some_and(|h| h.overheated)
+        {
+            secondary.can_fire = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::event::ManualEventReader,
+        prelude::{In, Resource},
+    };
+
+    use super::*;
+    use crate::{
+        projectile::{Projectile, ProjectileBundle},
+        testing::{sent, weapon_app, wielded},
+    };
+
+    const FRAME: f32 = 1. / 30.;
+
+    fn noop() -> Box<dyn Fn(&mut WeaponArguments) + Send + Sync> {
+        Box::new(|_| ())
+    }
+
+    fn pull(app: &mut App, weapon: Entity, target: Option<Entity>, action: WeaponAction) {
+        app.world_mut().send_event(FireWeaponEvent {
+            weapon,
+            target,
+            action,
+        });
+    }
+
+    #[derive(Resource)]
+    struct HeldTrigger(Entity);
+
+    fn hold_trigger(trigger: Res<HeldTrigger>, mut events: EventWriter<FireWeaponEvent>) {
+        events.send(FireWeaponEvent {
+            weapon: trigger.0,
+            target: None,
+            action: WeaponAction::Primary,
+        });
+    }
+
+    #[test]
+    fn held_full_auto_fires_as_soon_as_the_cooldown_runs_out() {
+        let mut app = weapon_app();
+        let weapon = wielded(app.world_mut(), noop(), WeaponFireMode::FullAuto);
+        app.world_mut()
+            .entity_mut(weapon)
+            .insert(Cooldown::new(0.125));
+        app.insert_resource(HeldTrigger(weapon))
+            .add_systems(Update, hold_trigger.in_set(WeaponTriggerSet));
+
+        let mut fired = ManualEventReader::<WeaponFiredEvent>::default();
+        let mut shots = 0;
+        for _ in 0..33 {
+            app.update();
+            shots += sent(&app, &mut fired).len();
+        }
+        // One shot every four frames, on the first frame and every frame the cooldown runs out.
+        assert_eq!(shots, 9);
+    }
+
+    #[derive(Resource, Default)]
+    struct FireInputs(Vec<WeaponFireInput>);
+
+    fn record_shot(In(input): In<WeaponFireInput>, mut inputs: ResMut<FireInputs>) {
+        inputs.0.push(input);
+    }
+
+    #[test]
+    fn fire_systems_get_the_shot() {
+        let mut app = weapon_app();
+        app.init_resource::<FireInputs>();
+        let system = app.world_mut().register_system(record_shot);
+        let world = app.world_mut();
+        let fire_func = Weapon::from_system(system, WeaponFireMode::SemiAuto).fire_func;
+        let weapon = wielded(world, fire_func, WeaponFireMode::SemiAuto);
+        world
+            .entity_mut(weapon)
+            .insert((Damage(4.), Muzzles::forward(10.)));
+        let target = world.spawn(Transform2d::default()).id();
+        pull(&mut app, weapon, Some(target), WeaponAction::Primary);
+        app.update();
+
+        let inputs = &app.world().resource::<FireInputs>().0;
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].weapon, weapon);
+        assert_eq!(inputs[0].target, Some(target));
+        assert_eq!(inputs[0].damage, 4.);
+        assert_eq!(inputs[0].muzzles, vec![Vec2::new(10., 0.)]);
+    }
+
+    #[test]
+    fn secondary_fire_has_its_own_cooldown() {
+        let mut app = weapon_app();
+        let weapon = wielded(app.world_mut(), noop(), WeaponFireMode::SemiAuto);
+        app.world_mut()
+            .entity_mut(weapon)
+            .insert(SecondaryFire::new(noop(), WeaponFireMode::SemiAuto, 0.5));
+        let mut fired = ManualEventReader::<WeaponFiredEvent>::default();
+        let mut rejected = ManualEventReader::<WeaponFireRejected>::default();
+
+        pull(&mut app, weapon, None, WeaponAction::Secondary);
+        app.update();
+        let actions: Vec<_> = sent(&app, &mut fired).iter().map(|e| e.action).collect();
+        assert_eq!(actions, vec![WeaponAction::Secondary]);
+
+        pull(&mut app, weapon, None, WeaponAction::Secondary);
+        pull(&mut app, weapon, None, WeaponAction::Primary);
+        app.update();
+        let actions: Vec<_> = sent(&app, &mut fired).iter().map(|e| e.action).collect();
+        assert_eq!(actions, vec![WeaponAction::Primary]);
+        let reasons: Vec<_> = sent(&app, &mut rejected).iter().map(|e| e.reason).collect();
+        assert_eq!(reasons, vec![FireRejectReason::OnCooldown]);
+    }
+
+    #[test]
+    fn unfireable_requests_are_rejected() {
+        let mut app = weapon_app();
+        let world = app.world_mut();
+        let held = wielded(world, noop(), WeaponFireMode::SemiAuto);
+        let dropped = world
+            .spawn(Weapon {
+                can_fire: true,
+                fire_func: noop(),
+                fire_mode: WeaponFireMode::SemiAuto,
+            })
+            .id();
+        let not_a_weapon = world.spawn_empty().id();
+        let mut rejected = ManualEventReader::<WeaponFireRejected>::default();
+
+        pull(&mut app, not_a_weapon, None, WeaponAction::Primary);
+        pull(&mut app, dropped, None, WeaponAction::Primary);
+        pull(&mut app, held, None, WeaponAction::Secondary);
+        app.update();
+        let reasons: Vec<_> = sent(&app, &mut rejected).iter().map(|e| e.reason).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                FireRejectReason::NoWeapon,
+                FireRejectReason::NoParent,
+                FireRejectReason::NoSuchAction,
+            ]
+        );
+    }
+
+    #[test]
+    fn muzzles_stay_put_under_bloom() {
+        let mut app = weapon_app();
+        let fire_func = FirePattern::Single.fire_func(100., ProjectileBundle::default());
+        let world = app.world_mut();
+        let weapon = wielded(world, fire_func, WeaponFireMode::SemiAuto);
+        world.entity_mut(weapon).insert(Muzzles::forward(20.));
+        let wielder = world.get::<Parent>(weapon).unwrap().get();
+        world.entity_mut(wielder).insert(AimSpread(1.));
+        let target = world
+            .spawn(Transform2d::from_translation(Vec2::new(100., 0.)))
+            .id();
+        for _ in 0..4 {
+            pull(&mut app, weapon, Some(target), WeaponAction::Primary);
+            app.update();
+        }
+
+        let mut projectiles = app
+            .world_mut()
+            .query_filtered::<&Transform2d, With<Projectile>>();
+        let shots: Vec<_> = projectiles
+            .iter(app.world())
+            .map(|t| (t.translation, t.rotation))
+            .collect();
+        assert_eq!(shots.len(), 4);
+        assert!(shots
+            .iter()
+            .all(|(origin, _)| *origin == Vec2::new(20., 0.)));
+        assert!(shots.iter().any(|(_, rotation)| *rotation != 0.));
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{actual:?} != {expected:?}");
+        }
+    }
+
+    fn ran_out(max: f32, delta: f32) -> Cooldown {
+        let mut cooldown = Cooldown::new(max);
+        cooldown.tick(Duration::from_secs_f32(delta));
+        cooldown
+    }
+
+    #[test]
+    fn fresh_pull_fires_once_at_age_zero() {
+        let mut cooldown = ran_out(0.03, 2.);
+        assert_close(&cooldown.restart_continuous(0.03, FRAME), &[0.]);
+        assert_eq!(cooldown.timer.elapsed_secs(), 0.);
+    }
+
+    #[test]
+    fn held_trigger_carries_time_over() {
+        let mut cooldown = ran_out(0.03, FRAME);
+        assert_close(&cooldown.restart_continuous(0.03, FRAME), &[FRAME - 0.03]);
+        assert!((cooldown.timer.elapsed_secs() - (FRAME - 0.03)).abs() < 1e-4);
+        assert_eq!(cooldown.overshoot, 0.);
+    }
+
+    #[test]
+    fn fast_weapon_fires_several_shots_per_frame() {
+        let mut cooldown = ran_out(0.01, FRAME);
+        let overshoot = FRAME - 0.01;
+        assert_close(
+            &cooldown.restart_continuous(0.01, FRAME),
+            &[overshoot, overshoot - 0.01, overshoot - 0.02],
+        );
+        assert!((cooldown.timer.elapsed_secs() - (overshoot - 0.02)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn zero_cooldown_fires_once() {
+        let mut cooldown = ran_out(0., FRAME);
+        assert_eq!(cooldown.restart_continuous(0., FRAME).len(), 1);
+    }
+
+    #[test]
+    fn shots_per_frame_are_capped() {
+        let mut cooldown = ran_out(0.0001, 0.5);
+        let ages = cooldown.restart_continuous(0.0001, 0.5);
+        assert_eq!(ages.len(), MAX_SHOTS_PER_FRAME);
+    }
+}
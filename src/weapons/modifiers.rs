@@ -0,0 +1,243 @@
+use bevy::{
+    ecs::system::EntityCommands,
+    prelude::{Component, Entity, Query, Reflect},
+};
+use std::{fmt, sync::Arc};
+
+use super::patterns::{FirePattern, Spread};
+
+/// A number on a weapon that modifiers can change.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub enum WeaponStat {
+    /// Seconds between shots. Multiply by `1. / 1.2` for "+20% fire rate".
+    Cooldown,
+    /// Projectiles per shot.
+    Count,
+    /// Angle in radians that the projectiles of one shot are fanned out over.
+    Spread,
+    Speed,
+    /// The weapon's `Knockback`, or the knockback of its `Beam` or `Melee`.
+    Knockback,
+    /// The weapon's `Damage`, or the damage per second of its `Beam`.
+    Damage,
+}
+
+/// How a modifier changes a stat. Regardless of the order modifiers were added in, every `Add`
+/// applies first, then every `Multiply`, then `Set`, so the last `Set` wins.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug)]
+pub enum ModifierOp {
+    Add(f32),
+    Multiply(f32),
+    Set(f32),
+}
+
+impl ModifierOp {
+    fn stage(&self) -> u8 {
+        match self {
+            ModifierOp::Add(_) => 0,
+            ModifierOp::Multiply(_) => 1,
+            ModifierOp::Set(_) => 2,
+        }
+    }
+
+    fn apply(&self, value: f32) -> f32 {
+        match *self {
+            ModifierOp::Add(amount) => value + amount,
+            ModifierOp::Multiply(factor) => value * factor,
+            ModifierOp::Set(new) => new,
+        }
+    }
+}
+
+/// Runs on every projectile a modified weapon spawns, e.g. to insert extra components.
+pub type ProjectileModifier = Arc<dyn Fn(&mut EntityCommands) + Send + Sync>;
+
+#[derive(Clone)]
+pub enum WeaponModifier {
+    Stat(WeaponStat, ModifierOp),
+    Projectile(ProjectileModifier),
+}
+
+impl WeaponModifier {
+    pub fn projectile(modifier: impl Fn(&mut EntityCommands) + Send + Sync + 'static) -> Self {
+        WeaponModifier::Projectile(Arc::new(modifier))
+    }
+}
+
+/// Handle for taking a modifier back out of a `WeaponModifiers`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Reflect, Debug)]
+pub struct ModifierId(u32);
+
+/// Upgrades on a weapon, or on an actor to affect every weapon it wields. The wielder's
+/// modifiers go after the weapon's own within each stage.
+#[derive(Clone, Default, Component)]
+pub struct WeaponModifiers {
+    entries: Vec<(ModifierId, WeaponModifier)>,
+    next_id: u32,
+}
+
+impl WeaponModifiers {
+    pub fn with(mut self, modifier: WeaponModifier) -> Self {
+        self.push(modifier);
+        self
+    }
+
+    pub fn push(&mut self, modifier: WeaponModifier) -> ModifierId {
+        let id = ModifierId(self.next_id);
+        self.next_id += 1;
+        self.entries.push((id, modifier));
+        id
+    }
+
+    pub fn remove(&mut self, id: ModifierId) -> Option<WeaponModifier> {
+        let index = self.entries.iter().position(|(i, _)| *i == id)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WeaponModifier> {
+        self.entries.iter().map(|(_, m)| m)
+    }
+}
+
+/// The modifiers of a weapon and its wielder, collected for a single shot and handed to fire
+/// functions as `WeaponArguments::modifiers`.
+#[derive(Clone, Default)]
+pub struct ShotModifiers {
+    stats: Vec<(WeaponStat, ModifierOp)>,
+    projectile: Vec<ProjectileModifier>,
+}
+
+impl fmt::Debug for ShotModifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShotModifiers")
+            .field("stats", &self.stats)
+            .field("projectile", &self.projectile.len())
+            .finish()
+    }
+}
+
+impl ShotModifiers {
+    pub fn collect(weapon: Option<&WeaponModifiers>, wielder: Option<&WeaponModifiers>) -> Self {
+        let mut collected = Self::default();
+        for modifier in weapon.into_iter().chain(wielder).flat_map(|m| m.iter()) {
+            match modifier {
+                WeaponModifier::Stat(stat, op) => collected.stats.push((*stat, *op)),
+                WeaponModifier::Projectile(f) => collected.projectile.push(f.clone()),
+            }
+        }
+        collected.stats.sort_by_key(|(_, op)| op.stage());
+        collected
+    }
+
+    /// The modifiers of `weapon` and `wielder`, for stats that are only read once a shot hits.
+    pub fn of(modifiers: &Query<&WeaponModifiers>, weapon: Entity, wielder: Entity) -> Self {
+        Self::collect(modifiers.get(weapon).ok(), modifiers.get(wielder).ok())
+    }
+
+    pub fn apply(&self, stat: WeaponStat, base: f32) -> f32 {
+        self.stats
+            .iter()
+            .filter(|(s, _)| *s == stat)
+            .fold(base, |value, (_, op)| op.apply(value))
+    }
+
+    pub fn count(&self, base: u32) -> u32 {
+        self.apply(WeaponStat::Count, base as f32).round().max(0.) as u32
+    }
+
+    /// `pattern` with its projectile count and spread modified. `Single` becomes a `Shotgun`
+    /// once it fires more than one projectile, so pair `Count` upgrades for single shot weapons
+    /// with a `Spread` one. `Alternating` is left alone.
+    pub fn pattern(&self, pattern: &FirePattern) -> FirePattern {
+        match *pattern {
+            FirePattern::Single if self.count(1) == 1 => FirePattern::Single,
+            FirePattern::Single => FirePattern::Shotgun {
+                count: self.count(1),
+                angle: self.apply(WeaponStat::Spread, 0.),
+                spread: Spread::Even,
+            },
+            FirePattern::Shotgun {
+                count,
+                angle,
+                spread,
+            } => FirePattern::Shotgun {
+                count: self.count(count),
+                angle: self.apply(WeaponStat::Spread, angle),
+                spread,
+            },
+            FirePattern::Parallel { count, spacing } => FirePattern::Parallel {
+                count: self.count(count),
+                spacing,
+            },
+            FirePattern::Ring { count } => FirePattern::Ring {
+                count: self.count(count),
+            },
+            FirePattern::Alternating { .. } => *pattern,
+        }
+    }
+
+    /// Run every projectile modifier on a freshly spawned projectile.
+    pub fn modify_projectile(&self, projectile: &mut EntityCommands) {
+        for modifier in self.projectile.iter() {
+            modifier(projectile);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(stat: WeaponStat, op: ModifierOp) -> WeaponModifier {
+        WeaponModifier::Stat(stat, op)
+    }
+
+    #[test]
+    fn adds_apply_before_multiplies_and_sets_win() {
+        let weapon = WeaponModifiers::default()
+            .with(stat(WeaponStat::Damage, ModifierOp::Multiply(2.)))
+            .with(stat(WeaponStat::Damage, ModifierOp::Add(3.)));
+        let shot = ShotModifiers::collect(Some(&weapon), None);
+        assert_eq!(shot.apply(WeaponStat::Damage, 1.), 8.);
+        assert_eq!(shot.apply(WeaponStat::Speed, 1.), 1.);
+
+        let wielder =
+            WeaponModifiers::default().with(stat(WeaponStat::Damage, ModifierOp::Set(5.)));
+        let shot = ShotModifiers::collect(Some(&weapon), Some(&wielder));
+        assert_eq!(shot.apply(WeaponStat::Damage, 1.), 5.);
+    }
+
+    #[test]
+    fn removed_modifiers_stop_applying() {
+        let mut modifiers = WeaponModifiers::default();
+        let id = modifiers.push(stat(WeaponStat::Cooldown, ModifierOp::Multiply(0.5)));
+        assert!(modifiers.remove(id).is_some());
+        assert!(modifiers.remove(id).is_none());
+        let shot = ShotModifiers::collect(Some(&modifiers), None);
+        assert_eq!(shot.apply(WeaponStat::Cooldown, 1.), 1.);
+    }
+
+    #[test]
+    fn extra_projectiles_turn_single_shots_into_shotguns() {
+        let modifiers = WeaponModifiers::default()
+            .with(stat(WeaponStat::Count, ModifierOp::Add(2.)))
+            .with(stat(WeaponStat::Spread, ModifierOp::Add(0.3)));
+        let shot = ShotModifiers::collect(Some(&modifiers), None);
+        assert_eq!(
+            shot.pattern(&FirePattern::Single),
+            FirePattern::Shotgun {
+                count: 3,
+                angle: 0.3,
+                spread: Spread::Even,
+            }
+        );
+        assert_eq!(
+            ShotModifiers::default().pattern(&FirePattern::Single),
+            FirePattern::Single
+        );
+    }
+}
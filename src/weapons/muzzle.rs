@@ -0,0 +1,90 @@
+use bevy::{
+    prelude::{Component, Vec2},
+    reflect::Reflect,
+};
+
+/// Where a weapon's shots come out, so projectiles don't spawn inside the wielder's own collider.
+/// Offsets are in pixels from the wielder, in a frame where +x points along the aim direction, so
+/// they turn with whatever the wielder is facing.
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct Muzzles {
+    pub offsets: Vec<Vec2>,
+    pub cycling: MuzzleCycling,
+    /// Muzzle the next `MuzzleCycling::RoundRobin` shot comes out of.
+    pub next: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug)]
+pub enum MuzzleCycling {
+    /// Every shot comes out of the next muzzle in turn.
+    RoundRobin,
+    /// Every shot comes out of all muzzles at once.
+    Simultaneous,
+}
+
+impl Muzzles {
+    pub fn new(offsets: Vec<Vec2>) -> Self {
+        Self {
+            offsets,
+            cycling: MuzzleCycling::RoundRobin,
+            next: 0,
+        }
+    }
+
+    /// A single muzzle `distance` pixels in front of the wielder.
+    pub fn forward(distance: f32) -> Self {
+        Self::new(vec![Vec2::new(distance, 0.)])
+    }
+
+    pub fn cycling(self, cycling: MuzzleCycling) -> Self {
+        Self { cycling, ..self }
+    }
+
+    /// The offsets the next shot fires from, advancing the round robin.
+    pub fn fire(&mut self) -> Vec<Vec2> {
+        match self.cycling {
+            MuzzleCycling::Simultaneous => self.offsets.clone(),
+            MuzzleCycling::RoundRobin if self.offsets.is_empty() => Vec::new(),
+            MuzzleCycling::RoundRobin => {
+                let index = self.next % self.offsets.len();
+                self.next = (index + 1) % self.offsets.len();
+                vec![self.offsets[index]]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn barrels() -> Vec<Vec2> {
+        vec![Vec2::new(10., -4.), Vec2::new(10., 4.)]
+    }
+
+    #[test]
+    fn round_robin_takes_turns() {
+        let mut muzzles = Muzzles::new(barrels());
+        let fired: Vec<_> = (0..3).map(|_| muzzles.fire()).collect();
+        assert_eq!(
+            fired,
+            vec![
+                vec![Vec2::new(10., -4.)],
+                vec![Vec2::new(10., 4.)],
+                vec![Vec2::new(10., -4.)],
+            ]
+        );
+    }
+
+    #[test]
+    fn simultaneous_fires_every_muzzle() {
+        let mut muzzles = Muzzles::new(barrels()).cycling(MuzzleCycling::Simultaneous);
+        assert_eq!(muzzles.fire(), barrels());
+        assert_eq!(muzzles.fire(), barrels());
+    }
+
+    #[test]
+    fn no_muzzles_fire_nothing() {
+        assert!(Muzzles::new(Vec::new()).fire().is_empty());
+    }
+}
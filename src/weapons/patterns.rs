@@ -0,0 +1,308 @@
+use bevy::{
+    prelude::{Entity, Vec2},
+    reflect::Reflect,
+};
+use bevy_rapier2d::prelude::Velocity;
+use bevy_turborand::prelude::{DelegatedRng, RngComponent};
+use serde::Deserialize;
+use std::{
+    f32::consts::TAU,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    projectile::{Knockback, ProjectileBundle, Shooter},
+    stats::Damage,
+};
+
+use super::{modifiers::WeaponStat, WeaponArguments};
+
+/// How the projectiles of a single shot are laid out around the aim direction.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Deserialize)]
+pub enum FirePattern {
+    Single,
+    /// `count` projectiles fanned out over a cone `angle` radians wide.
+    Shotgun {
+        count: u32,
+        angle: f32,
+        spread: Spread,
+    },
+    /// `count` projectiles flying side by side, `spacing` pixels apart.
+    Parallel {
+        count: u32,
+        spacing: f32,
+    },
+    /// `count` projectiles spread evenly around a full circle, starting at the aim direction.
+    Ring {
+        count: u32,
+    },
+    /// One projectile per shot, taking turns between `barrels` barrels `spacing` pixels apart.
+    Alternating {
+        barrels: u32,
+        spacing: f32,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Deserialize)]
+pub enum Spread {
+    Even,
+    Random,
+}
+
+/// Where a single projectile of a pattern starts and which way it flies.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug)]
+pub struct Shot {
+    pub origin: Vec2,
+    pub direction: Vec2,
+}
+
+impl FirePattern {
+    /// Lay the pattern out around `origin`, aimed along the normalized `direction`.
+    /// `shot_index` counts the weapon's shots and picks the barrel for `Alternating`.
+    pub fn shots(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        shot_index: usize,
+        rng: &mut RngComponent,
+    ) -> Vec<Shot> {
+        let side = direction.perp();
+        match *self {
+            FirePattern::Single => vec![Shot { origin, direction }],
+            FirePattern::Shotgun {
+                count,
+                angle,
+                spread,
+            } => (0..count)
+                .map(|i| {
+                    let offset = match spread {
+                        Spread::Even if count > 1 => angle * (i as f32 / (count - 1) as f32 - 0.5),
+                        Spread::Even => 0.,
+                        Spread::Random => angle * (rng.f32() - 0.5),
+                    };
+                    Shot {
+                        origin,
+                        direction: Vec2::from_angle(offset).rotate(direction),
+                    }
+                })
+                .collect(),
+            FirePattern::Parallel { count, spacing } => (0..count)
+                .map(|i| Shot {
+                    origin: origin + side * spacing * centered(i, count),
+                    direction,
+                })
+                .collect(),
+            FirePattern::Ring { count } => (0..count)
+                .map(|i| Shot {
+                    origin,
+                    direction: Vec2::from_angle(TAU * i as f32 / count as f32).rotate(direction),
+                })
+                .collect(),
+            FirePattern::Alternating { barrels, spacing } => {
+                let barrels = barrels.max(1);
+                let barrel = (shot_index % barrels as usize) as u32;
+                vec![Shot {
+                    origin: origin + side * spacing * centered(barrel, barrels),
+                    direction,
+                }]
+            }
+        }
+    }
+
+    /// A `Weapon::fire_func` that fires this pattern from the wielder toward the target or cursor.
+    pub fn fire_func(
+        self,
+        speed: f32,
+        template: ProjectileBundle,
+    ) -> Box<dyn Fn(&mut WeaponArguments) + Send + Sync> {
+        let shots_fired = AtomicUsize::new(0);
+        Box::new(move |args: &mut WeaponArguments| {
+            let shot_index = shots_fired.fetch_add(1, Ordering::Relaxed);
+            for projectile in fire_pattern(args, &self, shot_index, speed, &template) {
+                args.modifiers
+                    .modify_projectile(&mut args.commands.entity(projectile));
+            }
+        })
+    }
+}
+
+/// Offset of the `i`th of `count` evenly spaced items from their middle, in item spacings.
+fn centered(i: u32, count: u32) -> f32 {
+    i as f32 - count.saturating_sub(1) as f32 / 2.
+}
+
+/// The wielder's position and the normalized direction from it to the target, or to the cursor
+/// when there is no target. With an aim assist strength below `1` the direction is only bent from
+/// the cursor part of the way toward the target.
+pub fn aim(args: &WeaponArguments) -> Option<(Vec2, Vec2)> {
+    let origin = args.transforms.get(args.parent).ok()?.translation;
+    let toward = |entity: Entity| {
+        let aim_point = args.transforms.get(entity).ok()?.translation;
+        Some((aim_point - origin).try_normalize().unwrap_or(Vec2::X))
+    };
+    let direction = match (args.target.and_then(toward), toward(args.cursor)) {
+        (Some(target), Some(cursor)) if args.assist < 1. => {
+            Vec2::from_angle(cursor.angle_between(target) * args.assist.max(0.)).rotate(cursor)
+        }
+        (Some(target), _) => target,
+        (None, cursor) => cursor?,
+    };
+    Some((origin, direction))
+}
+
+/// `direction` knocked off course by up to half the weapon's bloom either way.
+pub fn bloom(args: &mut WeaponArguments, direction: Vec2) -> Vec2 {
+    let deviation = args.bloom * (args.rng.f32() - 0.5);
+    Vec2::from_angle(deviation).rotate(direction)
+}
+
+/// Like `aim`, but with the direction knocked off course by `bloom`.
+pub fn bloomed_aim(args: &mut WeaponArguments) -> Option<(Vec2, Vec2)> {
+    let (origin, direction) = aim(args)?;
+    Some((origin, bloom(args, direction)))
+}
+
+/// Where a shot from `origin` aimed along `direction` leaves the weapon: one point per muzzle the
+/// shot comes out of, or just `origin` for weapons without `Muzzles`. Pass the direction from
+/// `aim`, before any bloom, so the muzzles don't wobble with the spread.
+pub fn muzzle_origins(args: &WeaponArguments, origin: Vec2, direction: Vec2) -> Vec<Vec2> {
+    if args.muzzles.is_empty() {
+        return vec![origin];
+    }
+    args.muzzles
+        .iter()
+        .map(|offset| origin + direction.rotate(*offset))
+        .collect()
+}
+
+/// Spawn copies of `template` laid out by `pattern` around each of the shot's muzzles, each
+/// flying at `speed`, rotated to face its direction, fired by the wielder as its `Shooter` and
+/// sharing its `Faction` and the weapon's `Knockback` and `Damage`.
+/// Projectiles start as far along as the shot's age has carried them. Returns the spawned
+/// projectiles so callers can add their own components.
+///
+/// The weapon's count, spread and speed modifiers are applied here. Projectile modifiers are
+/// left to the caller, to run with `ShotModifiers::modify_projectile` after its own components.
+pub fn fire_pattern(
+    args: &mut WeaponArguments,
+    pattern: &FirePattern,
+    shot_index: usize,
+    speed: f32,
+    template: &ProjectileBundle,
+) -> Vec<Entity> {
+    let Some((origin, aim_direction)) = aim(args) else {
+        return Vec::new();
+    };
+    let direction = bloom(args, aim_direction);
+    let speed = args.modifiers.apply(WeaponStat::Speed, speed);
+    let pattern = args.modifiers.pattern(pattern);
+    let mut shots = Vec::new();
+    for origin in muzzle_origins(args, origin, aim_direction) {
+        shots.extend(pattern.shots(origin, direction, shot_index, &mut args.rng));
+    }
+    shots
+        .into_iter()
+        .map(|shot| {
+            let mut bundle = template.clone();
+            bundle.transform.translation = shot.origin + shot.direction * speed * args.shot_age;
+            bundle.transform.rotation = shot.direction.to_angle();
+            bundle.velocity = Velocity::linear(shot.direction * speed);
+            let mut projectile = args.commands.spawn(bundle);
+            projectile.insert(Shooter(args.parent));
+            if let Some(faction) = args.faction {
+                projectile.insert(faction);
+            }
+            if args.knockback > 0. {
+                projectile.insert(Knockback(args.knockback));
+            }
+            if args.damage > 0. {
+                projectile.insert(Damage(args.damage));
+            }
+            projectile.id()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn shots(pattern: FirePattern, shot_index: usize) -> Vec<Shot> {
+        pattern.shots(
+            Vec2::ZERO,
+            Vec2::X,
+            shot_index,
+            &mut RngComponent::with_seed(7),
+        )
+    }
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.distance(expected) < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn even_shotgun_fans_out_symmetrically() {
+        let shots = shots(
+            FirePattern::Shotgun {
+                count: 3,
+                angle: FRAC_PI_2,
+                spread: Spread::Even,
+            },
+            0,
+        );
+        let angles: Vec<f32> = shots.iter().map(|s| s.direction.to_angle()).collect();
+        assert_eq!(angles.len(), 3);
+        for (angle, expected) in angles.iter().zip([-FRAC_PI_2 / 2., 0., FRAC_PI_2 / 2.]) {
+            assert!((angle - expected).abs() < 1e-4, "{angles:?}");
+        }
+    }
+
+    #[test]
+    fn random_shotgun_stays_inside_its_cone() {
+        let shots = shots(
+            FirePattern::Shotgun {
+                count: 20,
+                angle: 0.5,
+                spread: Spread::Random,
+            },
+            0,
+        );
+        assert!(shots.iter().all(|s| s.direction.to_angle().abs() <= 0.25));
+    }
+
+    #[test]
+    fn parallel_shots_are_spaced_across_the_aim() {
+        let shots = shots(
+            FirePattern::Parallel {
+                count: 2,
+                spacing: 10.,
+            },
+            0,
+        );
+        assert_near(shots[0].origin, Vec2::new(0., -5.));
+        assert_near(shots[1].origin, Vec2::new(0., 5.));
+        assert!(shots.iter().all(|s| s.direction == Vec2::X));
+    }
+
+    #[test]
+    fn ring_covers_the_full_circle() {
+        let shots = shots(FirePattern::Ring { count: 4 }, 0);
+        for (shot, expected) in shots.iter().zip([Vec2::X, Vec2::Y, -Vec2::X, -Vec2::Y]) {
+            assert_near(shot.direction, expected);
+        }
+    }
+
+    #[test]
+    fn alternating_takes_turns_between_barrels() {
+        let pattern = FirePattern::Alternating {
+            barrels: 2,
+            spacing: 8.,
+        };
+        let origins: Vec<Vec2> = (0..3).map(|i| shots(pattern, i)[0].origin).collect();
+        assert_near(origins[0], Vec2::new(0., -4.));
+        assert_near(origins[1], Vec2::new(0., 4.));
+        assert_near(origins[2], Vec2::new(0., -4.));
+    }
+}
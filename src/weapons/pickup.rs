@@ -0,0 +1,280 @@
+use bevy::{
+    prelude::{
+        Added, BuildChildren, Commands, Component, Entity, Event, EventReader, EventWriter, Or,
+        Parent, Query, With,
+    },
+    reflect::Reflect,
+};
+use bevy_mod_transform2d::transform2d::Transform2d;
+use bevy_rapier2d::{
+    pipeline::CollisionEvent,
+    prelude::{ActiveEvents, Collider, Sensor},
+};
+
+use crate::player::Player;
+
+use super::{
+    beam::Beam,
+    burst::BurstState,
+    charge::ChargeState,
+    inventory::{ActiveWeaponChangedEvent, WeaponInventory},
+    melee::Melee,
+};
+
+/// A weapon lying in the world instead of being carried. It gets a sensor of `radius` so that a
+/// `Player` or any actor with a `WeaponInventory` can pick it up with a `PickUpWeaponEvent`.
+///
+/// The weapon keeps all of its other components while it's on the ground, so its ammo, heat and
+/// cooldown carry over between wielders.
+#[derive(Clone, PartialEq, Reflect, Debug, Component)]
+pub struct WeaponPickup {
+    pub radius: f32,
+    /// Actors currently overlapping the pickup's sensor.
+    pub in_reach: Vec<Entity>,
+}
+
+impl WeaponPickup {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            in_reach: Vec::new(),
+        }
+    }
+}
+
+impl Default for WeaponPickup {
+    fn default() -> Self {
+        Self::new(20.)
+    }
+}
+
+/// Pick up the nearest weapon in the actor's reach, if there is one.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct PickUpWeaponEvent {
+    pub actor: Entity,
+}
+
+/// Detach a weapon from its wielder and leave it in the world as a `WeaponPickup`.
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct DropWeaponEvent {
+    pub weapon: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct WeaponPickedUpEvent {
+    pub actor: Entity,
+    pub weapon: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Reflect, Debug, Event)]
+pub struct WeaponDroppedEvent {
+    pub actor: Entity,
+    pub weapon: Entity,
+}
+
+pub(crate) fn add_pickup_sensors(
+    mut commands: Commands,
+    pickups: Query<(Entity, &WeaponPickup), Added<WeaponPickup>>,
+) {
+    for (entity, pickup) in pickups.iter() {
+        commands.entity(entity).insert((
+            Collider::ball(pickup.radius),
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+        ));
+    }
+}
+
+pub(crate) fn track_pickup_reach(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut pickups: Query<&mut WeaponPickup>,
+    carriers: Query<(), Or<(With<Player>, With<WeaponInventory>)>>,
+) {
+    for collision_event in collision_events.read() {
+        let (e1, e2, started) = match collision_event {
+            CollisionEvent::Started(e1, e2, _) => (*e1, *e2, true),
+            CollisionEvent::Stopped(e1, e2, _) => (*e1, *e2, false),
+        };
+        for (pickup, actor) in [(e1, e2), (e2, e1)] {
+            if !carriers.contains(actor) {
+                continue;
+            }
+            if let Ok(mut pickup) = pickups.get_mut(pickup) {
+                pickup.in_reach.retain(|e| *e != actor);
+                if started {
+                    pickup.in_reach.push(actor);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn pick_up_weapons(
+    mut events: EventReader<PickUpWeaponEvent>,
+    mut picked_up_events: EventWriter<WeaponPickedUpEvent>,
+    mut changed_events: EventWriter<ActiveWeaponChangedEvent>,
+    mut commands: Commands,
+    pickups: Query<(Entity, &WeaponPickup, &Transform2d)>,
+    transforms: Query<&Transform2d>,
+    mut inventories: Query<&mut WeaponInventory>,
+) {
+    let mut taken = Vec::new();
+    for PickUpWeaponEvent { actor } in events.read() {
+        let Ok(origin) = transforms.get(*actor).map(|t| t.translation) else {
+            continue;
+        };
+        let Some((weapon, _, _)) = pickups
+            .iter()
+            .filter(|(e, p, _)| p.in_reach.contains(actor) && !taken.contains(e))
+            .min_by(|(_, _, a), (_, _, b)| {
+                let (a, b) = (a.translation, b.translation);
+                a.distance_squared(origin)
+                    .total_cmp(&b.distance_squared(origin))
+            })
+        else {
+            continue;
+        };
+        taken.push(weapon);
+        commands
+            .entity(weapon)
+            .remove::<(WeaponPickup, Collider, Sensor, ActiveEvents)>()
+            .insert(Transform2d::default())
+            .set_parent(*actor);
+        if let Ok(mut inventory) = inventories.get_mut(*actor) {
+            inventory.push(weapon);
+            if inventory.slots.len() == 1 {
+                changed_events.send(ActiveWeaponChangedEvent {
+                    actor: *actor,
+                    previous: None,
+                    current: Some(weapon),
+                });
+            }
+        }
+        picked_up_events.send(WeaponPickedUpEvent {
+            actor: *actor,
+            weapon,
+        });
+    }
+}
+
+pub(crate) fn drop_weapons(
+    mut events: EventReader<DropWeaponEvent>,
+    mut dropped_events: EventWriter<WeaponDroppedEvent>,
+    mut changed_events: EventWriter<ActiveWeaponChangedEvent>,
+    mut commands: Commands,
+    mut weapons: Query<(
+        &Parent,
+        Option<&mut BurstState>,
+        Option<&mut ChargeState>,
+        Option<&mut Beam>,
+        Option<&mut Melee>,
+    )>,
+    transforms: Query<&Transform2d>,
+    mut inventories: Query<&mut WeaponInventory>,
+) {
+    for DropWeaponEvent { weapon } in events.read() {
+        let Ok((parent, burst, charge, beam, melee)) = weapons.get_mut(*weapon) else {
+            continue;
+        };
+        let actor = parent.get();
+        if let Some(mut burst) = burst {
+            burst.cancel();
+        }
+        if let Some(mut charge) = charge {
+            charge.charging = false;
+            charge.elapsed = 0.;
+        }
+        if let Some(mut beam) = beam {
+            beam.stop(&mut commands);
+        }
+        if let Some(mut melee) = melee {
+            melee.swinging = false;
+        }
+        if let Ok(mut inventory) = inventories.get_mut(actor) {
+            let previous = inventory.active_weapon();
+            inventory.remove(*weapon);
+            if previous != inventory.active_weapon() {
+                changed_events.send(ActiveWeaponChangedEvent {
+                    actor,
+                    previous,
+                    current: inventory.active_weapon(),
+                });
+            }
+        }
+        let translation = transforms
+            .get(actor)
+            .map(|t| t.translation)
+            .unwrap_or_default();
+        commands.entity(*weapon).remove_parent().insert((
+            Transform2d::from_translation(translation),
+            WeaponPickup::default(),
+        ));
+        dropped_events.send(WeaponDroppedEvent {
+            actor,
+            weapon: *weapon,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec2;
+    use bevy_rapier2d::rapier::prelude::CollisionEventFlags;
+
+    use super::*;
+    use crate::{
+        testing::weapon_app,
+        weapons::{Weapon, WeaponFireMode},
+    };
+
+    #[test]
+    fn weapons_are_picked_up_in_reach_and_dropped_again() {
+        let mut app = weapon_app();
+        let world = app.world_mut();
+        let weapon = world
+            .spawn((
+                Weapon {
+                    can_fire: true,
+                    fire_func: Box::new(|_| ()),
+                    fire_mode: WeaponFireMode::SemiAuto,
+                },
+                WeaponPickup::default(),
+                Transform2d::from_translation(Vec2::new(10., 0.)),
+            ))
+            .id();
+        let actor = world
+            .spawn((
+                WeaponInventory::new(Vec::new(), 0.),
+                Transform2d::from_translation(Vec2::new(0., 30.)),
+            ))
+            .id();
+
+        // Out of reach, nothing happens.
+        app.world_mut().send_event(PickUpWeaponEvent { actor });
+        app.update();
+        assert!(app.world().get::<Parent>(weapon).is_none());
+
+        let flags = CollisionEventFlags::SENSOR;
+        app.world_mut()
+            .send_event(CollisionEvent::Started(weapon, actor, flags));
+        app.update();
+        let pickup = app.world().get::<WeaponPickup>(weapon).unwrap();
+        assert_eq!(pickup.in_reach, vec![actor]);
+
+        app.world_mut().send_event(PickUpWeaponEvent { actor });
+        app.update();
+        assert_eq!(app.world().get::<Parent>(weapon).unwrap().get(), actor);
+        assert!(app.world().get::<WeaponPickup>(weapon).is_none());
+        let inventory = app.world().get::<WeaponInventory>(actor).unwrap();
+        assert_eq!(inventory.active_weapon(), Some(weapon));
+
+        app.world_mut().send_event(DropWeaponEvent { weapon });
+        app.update();
+        assert!(app.world().get::<Parent>(weapon).is_none());
+        assert!(app.world().get::<WeaponPickup>(weapon).is_some());
+        let dropped_at = app.world().get::<Transform2d>(weapon).unwrap().translation;
+        assert_eq!(dropped_at, Vec2::new(0., 30.));
+        let inventory = app.world().get::<WeaponInventory>(actor).unwrap();
+        assert!(inventory.slots.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::{
+    prelude::{Component, Entity, EventReader, Query, Res, With},
+    reflect::Reflect,
+    time::Time,
+};
+use bevy_mod_transform2d::transform2d::Transform2d;
+use bevy_rapier2d::prelude::ExternalImpulse;
+
+use crate::player::CursorTracker;
+
+use super::WeaponFiredEvent;
+
+/// Kick from firing a weapon: every shot shoves the wielder away from the aim point and widens
+/// the weapon's aim spread ("bloom"), which then recovers over time.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Component)]
+pub struct Recoil {
+    /// Impulse applied to the wielder per shot.
+    pub impulse: f32,
+    /// Radians of spread added per shot.
+    pub bloom_per_shot: f32,
+    pub max_bloom: f32,
+    /// Radians of spread recovered per second.
+    pub recovery: f32,
+    /// Current spread in radians, handed to fire functions as `WeaponArguments::bloom`.
+    pub bloom: f32,
+}
+
+impl Recoil {
+    pub fn new(impulse: f32) -> Self {
+        Self {
+            impulse,
+            bloom_per_shot: 0.,
+            max_bloom: 0.,
+            recovery: 0.,
+            bloom: 0.,
+        }
+    }
+
+    pub fn bloom(self, bloom_per_shot: f32, max_bloom: f32) -> Self {
+        Self {
+            bloom_per_shot,
+            max_bloom,
+            ..self
+        }
+    }
+
+    pub fn recovery(self, recovery: f32) -> Self {
+        Self { recovery, ..self }
+    }
+}
+
+/// Extra aim spread in radians for every weapon an actor wields, e.g. to make AI shooters miss.
+/// Added on top of each weapon's own bloom.
+#[derive(Clone, Copy, PartialEq, Reflect, Debug, Component)]
+pub struct AimSpread(pub f32);
+
+pub(crate) fn apply_recoil(
+    mut events: EventReader<WeaponFiredEvent>,
+    mut weapons: Query<&mut Recoil>,
+    mut impulses: Query<&mut ExternalImpulse>,
+    cursor: Query<Entity, With<CursorTracker>>,
+    transforms: Query<&Transform2d>,
+) {
+    for WeaponFiredEvent {
+        weapon,
+        wielder,
+        target,
+        ..
+    } in events.read()
+    {
+        let Ok(mut recoil) = weapons.get_mut(*weapon) else {
+            continue;
+        };
+        recoil.bloom = (recoil.bloom + recoil.bloom_per_shot).min(recoil.max_bloom);
+
+        let aim_point = target
+            .or(cursor.get_single().ok())
+            .and_then(|e| transforms.get(e).ok());
+        let (Some(aim_point), Ok(wielder_transform)) = (aim_point, transforms.get(*wielder)) else {
+            continue;
+        };
+        let Some(direction) =
+            (aim_point.translation - wielder_transform.translation).try_normalize()
+        else {
+            continue;
+        };
+        if let Ok(mut impulse) = impulses.get_mut(*wielder) {
+            impulse.impulse -= direction * recoil.impulse;
+        }
+    }
+}
+
+pub(crate) fn recover_bloom(mut weapons: Query<&mut Recoil>, time: Res<Time>) {
+    for mut recoil in weapons.iter_mut().filter(|r| r.bloom > 0.) {
+        recoil.bloom = (recoil.bloom - recoil.recovery * time.delta_seconds()).max(0.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Parent, Vec2};
+
+    use super::*;
+    use crate::{
+        testing::{weapon_app, wielded},
+        weapons::{FireWeaponEvent, WeaponAction, WeaponFireMode},
+    };
+
+    #[test]
+    fn shots_kick_the_wielder_and_bloom_up_to_the_max() {
+        let mut app = weapon_app();
+        let world = app.world_mut();
+        let weapon = wielded(world, Box::new(|_| ()), WeaponFireMode::SemiAuto);
+        let recoil = Recoil::new(50.).bloom(0.5, 1.25).recovery(2.);
+        world.entity_mut(weapon).insert(recoil);
+        let wielder = world.get::<Parent>(weapon).unwrap().get();
+        world.entity_mut(wielder).insert(ExternalImpulse::default());
+        let target = world
+            .spawn(Transform2d::from_translation(Vec2::new(100., 0.)))
+            .id();
+
+        for _ in 0..3 {
+            app.world_mut().send_event(FireWeaponEvent {
+                weapon,
+                target: Some(target),
+                action: WeaponAction::Primary,
+            });
+            app.update();
+        }
+
+        let impulse = app.world().get::<ExternalImpulse>(wielder).unwrap().impulse;
+        assert_eq!(impulse, Vec2::new(-150., 0.));
+        // Capped at 1.25 by the third shot, minus a 1/32 s frame of recovery.
+        let bloom = app.world().get::<Recoil>(weapon).unwrap().bloom;
+        assert_eq!(bloom, 1.25 - 2. / 32.);
+    }
+}